@@ -1,14 +1,49 @@
 use std::cmp::Ordering;
 
-use structure::graph::Graph;
+use structure::graph::{Graph, GraphResult};
 
-use crate::game::{AIR_TRAVEL_TIME, LAND_TRAVEL_TIME, SEA_TRAVEL_TIME};
+use crate::game::{AIR_TRAVEL_TIME, GRAVITY_CONSTANT, LAND_TRAVEL_TIME, SEA_TRAVEL_TIME};
 
 pub struct Chunk {
     population: usize,
     size: f64,
 }
 
+impl Chunk {
+    pub fn new(population: usize, size: f64) -> Self {
+        Chunk { population, size }
+    }
+
+    pub fn population(&self) -> usize {
+        self.population
+    }
+
+    pub fn size(&self) -> f64 {
+        self.size
+    }
+
+    /// Moves `n` people into the chunk
+    pub fn add_population(&mut self, n: usize) {
+        self.population += n;
+    }
+
+    /// Moves up to `n` people out of the chunk, stopping once it's empty
+    ///
+    /// ###Return
+    /// The amount of people that were actually removed
+    pub fn remove_population(&mut self, n: usize) -> usize {
+        let removed = usize::min(n, self.population);
+        self.population -= removed;
+        removed
+    }
+
+    /// The amount of people per unit of size in the chunk
+    pub fn density(&self) -> f64 {
+        self.population as f64 / self.size
+    }
+}
+
+#[derive(Clone)]
 pub enum Adjacency {
     Land(f64),
     Water(f64),
@@ -40,3 +75,80 @@ impl PartialEq for Adjacency {
 pub struct GameBoard {
     chunk_graph: Graph<usize, Adjacency, Chunk>,
 }
+
+impl GameBoard {
+    pub fn new() -> Self {
+        GameBoard {
+            chunk_graph: Graph::new(),
+        }
+    }
+
+    pub fn add_chunk(&mut self, id: usize, chunk: Chunk) -> GraphResult<usize> {
+        self.chunk_graph.add_node(id, chunk)
+    }
+
+    /// Connects two chunks in both directions
+    pub fn connect(&mut self, a: usize, b: usize, adjacency: Adjacency) -> GraphResult<usize> {
+        self.chunk_graph.add_edge(a, b, adjacency.clone())?;
+        self.chunk_graph.add_edge(b, a, adjacency)
+    }
+
+    pub fn chunk_graph(&self) -> &Graph<usize, Adjacency, Chunk> {
+        &self.chunk_graph
+    }
+
+    /// Gets how much travel there is from chunk `a` to chunk `b`, following a gravity model where
+    /// the amount of travel between the two chunks scales with the product of their populations
+    /// divided by the square of their travel time. This is the expected amount of people that
+    /// travel along the connection every run, which is the same both ways along a connection.
+    pub fn travel_probability(&self, a: usize, b: usize) -> f64 {
+        let travel_time = match self.chunk_graph.get_weight(a, b) {
+            None => return 0.0,
+            Some(adjacency) => adjacency.get_travel_time(),
+        };
+        let source = self.chunk_graph.get(&a).unwrap();
+        let destination = self.chunk_graph.get(&b).unwrap();
+        GRAVITY_CONSTANT * source.population as f64 * destination.population as f64
+            / travel_time.powi(2)
+    }
+
+    /// Moves people from chunk `from` to chunk `to`, returning how many actually moved
+    pub fn relocate(&mut self, from: usize, to: usize, count: usize) -> usize {
+        if !self.chunk_graph.contains_edge(from, to) {
+            return 0;
+        }
+        let moved = self
+            .chunk_graph
+            .get_mut(&from)
+            .unwrap()
+            .remove_population(count);
+        self.chunk_graph.get_mut(&to).unwrap().add_population(moved);
+        moved
+    }
+}
+
+impl Default for GameBoard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::game::board::Chunk;
+
+    #[test]
+    fn population_changes_are_clamped() {
+        let mut chunk = Chunk::new(100, 4.0);
+        chunk.add_population(20);
+        assert_eq!(chunk.population(), 120);
+        assert_eq!(chunk.density(), 30.0);
+
+        assert_eq!(chunk.remove_population(50), 50);
+        assert_eq!(chunk.population(), 70);
+        assert_eq!(chunk.remove_population(100), 70);
+        assert_eq!(chunk.population(), 0);
+        assert_eq!(chunk.remove_population(1), 0);
+        assert_eq!(chunk.size(), 4.0);
+    }
+}
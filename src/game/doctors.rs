@@ -1 +1,106 @@
+use std::sync::{Arc, Mutex};
 
+use crate::game::playable::Player;
+use crate::game::population::person_behavior::Controller;
+use crate::game::population::Population;
+use crate::game::roll;
+
+///
+/// Treats the infected using the player's cure research. Every run, each active infection is
+/// cured with a chance equal to the cure progress for its pathogen.
+pub struct TreatmentController {
+    population: Arc<Mutex<Population>>,
+    player: Arc<Mutex<Player>>,
+    cured: usize,
+}
+
+impl TreatmentController {
+    pub fn new(population: &Arc<Mutex<Population>>, player: &Arc<Mutex<Player>>) -> Self {
+        Self {
+            population: population.clone(),
+            player: player.clone(),
+            cured: 0,
+        }
+    }
+
+    pub fn player(&self) -> &Arc<Mutex<Player>> {
+        &self.player
+    }
+
+    /// The total amount of infections that have been cured
+    pub fn cured(&self) -> usize {
+        self.cured
+    }
+}
+
+impl Controller for TreatmentController {
+    fn run(&mut self) {
+        let population = self
+            .population
+            .lock()
+            .expect("Should have been able to receive population");
+        let player = self
+            .player
+            .lock()
+            .expect("Should have been able to receive player");
+
+        for person in population.get_infected() {
+            let mut person = person.write().unwrap();
+            let pathogens = person
+                .infections()
+                .into_iter()
+                .filter(|i| !i.recovered())
+                .map(|i| i.get_pathogen().clone())
+                .collect::<Vec<_>>();
+
+            for pathogen in pathogens {
+                if roll(player.cure_progress(&pathogen)) && person.cure(pathogen.name()) {
+                    self.cured += 1;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use crate::game::doctors::TreatmentController;
+    use crate::game::pathogen::types::{PathogenType, Virus};
+    use crate::game::playable::{Player, RESEARCH_PER_SYMPTOM};
+    use crate::game::population::person_behavior::Controller;
+    use crate::game::population::{PersonBuilder, Population, UniformDistribution};
+
+    /// Treats 100 infected people once and gets how many were cured
+    fn cured_with_research(points: usize) -> usize {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            200,
+            UniformDistribution::new(20, 60),
+        );
+        let pathogen = Arc::new(Virus.default());
+        for _ in 0..100 {
+            assert!(pop.infect_one(&pathogen));
+        }
+
+        let mut player = Player::new();
+        player.invest(points);
+        let mut controller =
+            TreatmentController::new(&Arc::new(Mutex::new(pop)), &Arc::new(Mutex::new(player)));
+        controller.run();
+        controller.cured()
+    }
+
+    #[test]
+    fn research_improves_treatment() {
+        let none = cured_with_research(0);
+        let some = cured_with_research(RESEARCH_PER_SYMPTOM / 10);
+        let full = cured_with_research(RESEARCH_PER_SYMPTOM);
+
+        assert_eq!(none, 0);
+        assert!(some < full, "Partial research cured {}, full cured {}", some, full);
+        assert_eq!(full, 100);
+    }
+}
@@ -1,15 +1,16 @@
 use std::borrow::Borrow;
 use std::cmp::Ordering;
 use std::ops::{AddAssign, Deref};
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, PoisonError, RwLock};
 use std::thread::sleep;
 use std::time::Duration;
 
 use rand::Rng;
 use rayon::prelude::*;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use structure::time::{FineGrainTimeType, Time, TimeUnit, YearsType};
-use structure::time::TimeUnit::{Days, Months, Years};
+use structure::time::TimeUnit::{Days, Minutes, Months, Years};
 
 use crate::game;
 
@@ -18,10 +19,14 @@ pub mod doctors;
 pub mod pathogen;
 pub mod playable;
 pub mod population;
+pub mod recorder;
+pub mod simulation;
 
 pub static LAND_TRAVEL_TIME: f64 = 45.0;
 pub static SEA_TRAVEL_TIME: f64 = 100.0;
 pub static AIR_TRAVEL_TIME: f64 = 500.0;
+/// Scales the gravity model used for travel between chunks
+pub static GRAVITY_CONSTANT: f64 = 0.01;
 
 const TICKS_TO_GAME_MIN: usize = 20;
 
@@ -46,8 +51,11 @@ impl<T> Update for Arc<RwLock<T>>
 where
     T: Update<T>,
 {
+    /// A lock poisoned by an earlier panic is recovered, so the update can still go on
     fn update_self(&mut self, delta_time: usize) {
-        self.write().unwrap().update_self(delta_time)
+        self.write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .update_self(delta_time)
     }
 }
 
@@ -55,8 +63,11 @@ impl<T> Update for RwLock<T>
 where
     T: Update<T>,
 {
+    /// A lock poisoned by an earlier panic is recovered, so the update can still go on
     fn update_self(&mut self, delta_time: usize) {
-        self.write().unwrap().update_self(delta_time)
+        self.write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .update_self(delta_time)
     }
 }
 
@@ -115,10 +126,15 @@ pub fn tick_to_game_time_conversion(delta_time: usize) -> TimeUnit {
 }
 
 pub fn roll(chance: f64) -> bool {
+    roll_with(chance, &mut rand::thread_rng())
+}
+
+/// Rolls `chance` like [roll], using `rng` instead of the thread's rng
+pub fn roll_with<R: Rng>(chance: f64, rng: &mut R) -> bool {
     if chance < 0.0 || chance > 1.0 {
         panic!("Invalid chance: {}", chance);
     }
-    rand::thread_rng().gen_bool(chance)
+    rng.gen_bool(chance)
 }
 
 #[derive(Debug, Clone)]
@@ -140,6 +156,27 @@ impl Age {
     pub fn time_unit_mut(&mut self) -> &mut TimeUnit {
         &mut self.0
     }
+
+    /// Renders the age with a format string, such as `"{:y}y {:M(12M)}M"`
+    ///
+    /// See `TimeUnit::format` for the syntax
+    pub fn format(&self, format_string: &str) -> String {
+        self.0.format(format_string)
+    }
+}
+
+impl Serialize for Age {
+    /// Ages are serialized as their amount of minutes
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(usize::from(self.0.as_minutes()) as u64)
+    }
+}
+
+impl<'de> Deserialize<'de> for Age {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let minutes = u64::deserialize(deserializer)?;
+        Ok(Age(Minutes(minutes as usize)))
+    }
 }
 
 impl From<TimeUnit> for Age {
@@ -178,6 +215,27 @@ impl PartialOrd<TimeUnit> for Age {
     }
 }
 
+impl PartialEq for Age {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Age {}
+
+impl PartialOrd for Age {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Age {
+    /// Ages are compared by their canonical amount of minutes
+    fn cmp(&self, other: &Self) -> Ordering {
+        usize::from(self.0.as_minutes()).cmp(&usize::from(other.0.as_minutes()))
+    }
+}
+
 impl Update for Age {
     fn update_self(&mut self, delta_time: usize) {
         *self += game::tick_to_game_time_conversion(delta_time);
@@ -189,6 +247,7 @@ impl Update for Age {
 mod test {
     use std::borrow::BorrowMut;
 
+    use structure::time::Time;
     use structure::time::TimeUnit::{Days, Minutes, Years};
 
     use crate::game::{Age, Update};
@@ -233,6 +292,39 @@ mod test {
         assert_eq!(age, Years(21) + Days(21) + Minutes(1));
     }
 
+    #[test]
+    fn sort_ages() {
+        let mut ages: Vec<Age> = vec![
+            Years(30).into(),
+            (Years(2) + Days(5)).into(),
+            Age::new(2, 0, 4),
+            Minutes(15).into(),
+            Age::new(30, 0, 0),
+        ];
+        ages.sort();
+        let expected: Vec<Age> = vec![
+            Minutes(15).into(),
+            Age::new(2, 0, 4),
+            (Years(2) + Days(5)).into(),
+            Years(30).into(),
+            Years(30).into(),
+        ];
+        assert_eq!(ages, expected);
+        assert!(ages[1] < ages[2]);
+        assert_eq!(ages.iter().max().unwrap(), &Age::new(30, 0, 0));
+    }
+
+    #[test]
+    fn age_serialization() {
+        let age = Age::new(34, 5, 10);
+        let json = serde_json::to_string(&age).unwrap();
+        assert_eq!(json, usize::from(Age::new(34, 5, 10).time_unit().as_minutes()).to_string());
+        let read: Age = serde_json::from_str(&json).unwrap();
+        assert_eq!(read, age);
+
+        assert_eq!(age.format("{:y}y {:M(12M)}M"), "34y 5M");
+    }
+
     #[test]
     fn update_tree() {
         let mut tree = UpdateObject::new(Some((
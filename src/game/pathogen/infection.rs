@@ -4,12 +4,38 @@ use std::sync::Arc;
 use rand::distributions::Distribution;
 use rand::Rng;
 
-use structure::time::TimeUnit;
-use structure::time::TimeUnit::Minutes;
+use structure::time::{Time, TimeOffset, TimeUnit};
+use structure::time::TimeUnit::{Hours, Minutes};
 
-use crate::game::{Age, roll, tick_to_game_time_conversion, Update};
+use crate::game::{Age, roll_with, tick_to_game_time_conversion, Update};
 use crate::game::pathogen::Pathogen;
 
+/// The amount of pathogens an infection starts with when no dose is specified
+pub const DEFAULT_INITIAL_DOSE: usize = 100;
+
+/// How long a generation of pathogens takes inside an active infection, each of which has a
+/// chance to mutate the carried pathogen
+pub const PATHOGEN_GENERATION: TimeUnit = Hours(1);
+
+/// The fraction of an infection's active period that its early, prodromal stage lasts for
+pub const PRODROMAL_FRACTION: f64 = 0.2;
+/// The fraction of an infection's active period after which it starts to resolve
+pub const RESOLVING_FRACTION: f64 = 0.8;
+
+/// How far an infection has progressed, in the order that every infection goes through them
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum InfectionStage {
+    /// The pathogens are still multiplying, and there aren't enough for an active case yet
+    Incubating,
+    /// The start of the active case, with only early symptoms
+    Prodromal,
+    /// The height of the active case
+    Active,
+    /// The end of the active case, as the host gets over it
+    Resolving,
+    Recovered,
+}
+
 #[derive(Clone)]
 pub struct Infection {
     pathogen: Arc<Pathogen>, // pathogen
@@ -17,10 +43,36 @@ pub struct Infection {
     predetermined_duration: TimeUnit,
     pathogen_count: usize,
     recovered: bool, // if the person has recovered
+    time_since_recovery: TimeUnit,
+    fatal: bool, // if the outcome was decided at infection time to be death
+    minutes_since_generation: usize,
+    frozen: bool, // if updates are paused
+    became_active: Option<TimeUnit>, // the age of the infection when it became an active case
 }
 
 impl Infection {
     pub fn new(pathogen: Arc<Pathogen>, condition: f64) -> Self {
+        Self::with_dose(pathogen, condition, DEFAULT_INITIAL_DOSE)
+    }
+
+    /// Creates an infection, sampling its duration with `rng` so that a seeded rng always gives
+    /// the same duration for the same condition
+    pub fn new_seeded<R: Rng>(pathogen: Arc<Pathogen>, condition: f64, rng: &mut R) -> Self {
+        Self::with_dose_rng(pathogen, condition, DEFAULT_INITIAL_DOSE, rng)
+    }
+
+    /// Creates an infection that starts with `dose` pathogens
+    pub fn with_dose(pathogen: Arc<Pathogen>, condition: f64, dose: usize) -> Self {
+        Self::with_dose_rng(pathogen, condition, dose, &mut rand::thread_rng())
+    }
+
+    /// Creates an infection that starts with `dose` pathogens, sampling its duration with `rng`
+    pub fn with_dose_rng<R: Rng>(
+        pathogen: Arc<Pathogen>,
+        condition: f64,
+        dose: usize,
+        rng: &mut R,
+    ) -> Self {
         if pathogen.average_recovery_time() <= pathogen.base_recovery_distance() {
             panic!(
                 "Pathogen recovery range {} is greater than the average recovery time {}",
@@ -37,16 +89,27 @@ impl Infection {
             + (pathogen.base_recovery_distance() as f64 / condition) as usize;
 
         let duration = if min_duration == max_duration {
-            Minutes(min_duration)
+            min_duration
+        } else {
+            rng.gen_range(min_duration, max_duration)
+        };
+        let duration = Minutes(usize::max(duration, pathogen.minimum_infectious_period()));
+        let became_active = if dose > pathogen.min_count_for_symptoms() {
+            Some(Minutes(0))
         } else {
-            Minutes(rand::thread_rng().gen_range(min_duration, max_duration))
+            None
         };
         Infection {
             pathogen,
             infection_age: Age::new(0, 0, 0),
             predetermined_duration: duration,
-            pathogen_count: 100,
+            pathogen_count: dose,
             recovered: false,
+            time_since_recovery: Minutes(0),
+            fatal: false,
+            minutes_since_generation: 0,
+            frozen: false,
+            became_active,
         }
     }
 
@@ -54,6 +117,13 @@ impl Infection {
         &self.pathogen
     }
 
+    /// Replaces the carried pathogen, such as with a strain that evolved within the host, keeping
+    /// the age and the load of the infection. A host still files the infection under the name of
+    /// the pathogen they caught.
+    pub fn set_pathogen(&mut self, pathogen: Arc<Pathogen>) {
+        self.pathogen = pathogen;
+    }
+
     pub fn active_case(&self) -> bool {
         !self.recovered && self.pathogen_count > self.pathogen.min_count_for_symptoms
     }
@@ -68,19 +138,178 @@ impl Infection {
         }
     }
 
+    /// Whether the infection was decided to kill its host once it runs its course
+    pub fn fatal(&self) -> bool {
+        self.fatal
+    }
+
+    pub(crate) fn set_fatal(&mut self, fatal: bool) {
+        self.fatal = fatal;
+    }
+
+    /// How far the infection is through its predetermined duration, in the range [0, 1]
+    pub fn progress(&self) -> f64 {
+        let age = usize::from(self.infection_age.time_unit().as_minutes()) as f64;
+        let duration = usize::from(self.predetermined_duration.as_minutes()) as f64;
+        if duration == 0.0 {
+            return 1.0;
+        }
+        f64::min(1.0, age / duration)
+    }
+
+    /// The stage the infection is in. An active case goes through its stages in proportion to how
+    /// much of the time between becoming active and the infection's predetermined duration has
+    /// passed.
+    pub fn stage(&self) -> InfectionStage {
+        if self.recovered {
+            return InfectionStage::Recovered;
+        }
+        if !self.active_case() {
+            return InfectionStage::Incubating;
+        }
+        let duration = usize::from(self.predetermined_duration.as_minutes());
+        let active_at = self
+            .became_active
+            .as_ref()
+            .map_or(0, |age| usize::from(age.as_minutes()));
+        let age = usize::from(self.infection_age.time_unit().as_minutes());
+        let active_period = duration.saturating_sub(active_at);
+        let progress = if active_period == 0 {
+            1.0
+        } else {
+            age.saturating_sub(active_at) as f64 / active_period as f64
+        };
+        if progress < PRODROMAL_FRACTION {
+            InfectionStage::Prodromal
+        } else if progress < RESOLVING_FRACTION {
+            InfectionStage::Active
+        } else {
+            InfectionStage::Resolving
+        }
+    }
+
+    /// Recovers from the infection immediately, no matter how long it has lasted
+    pub fn cure(&mut self) {
+        self.recovered = true;
+    }
+
+    /// Whether the infection has recovered but is still within the pathogen's shedding window
+    pub fn shedding(&self) -> bool {
+        self.recovered && self.time_since_recovery < self.pathogen.shedding_duration()
+    }
+
+    /// Whether the infection can currently be spread, either as an active case or by shedding
+    pub fn transmissible(&self) -> bool {
+        self.active_case() || self.shedding()
+    }
+
+    /// Whether the infection can be spread by the body of its host once they're dead, which is
+    /// only the case for infections they died with if the pathogen stays infectious post mortem
+    pub fn post_mortem_transmissible(&self) -> bool {
+        (!self.recovered || self.fatal) && self.pathogen.post_mortem_infectious_period() > 0
+    }
+
+    /// The catch chance of the infection, reduced while only shedding
+    pub fn catch_chance(&self) -> f64 {
+        self.shedding_scaled(self.pathogen.catch_chance())
+    }
+
+    /// The catch chance of the infection at `time_of_year`, reduced while only shedding
+    pub fn catch_chance_at(&self, time_of_year: &TimeUnit) -> f64 {
+        self.shedding_scaled(self.pathogen.catch_chance_at(time_of_year))
+    }
+
+    fn shedding_scaled(&self, catch_chance: f64) -> f64 {
+        // a fatal infection only ends with its host's death, so it's spread by their body
+        if self.recovered && !self.fatal {
+            catch_chance * self.pathogen.shedding_infectivity()
+        } else {
+            catch_chance
+        }
+    }
+
+    /// The current amount of pathogens in the host
+    pub fn pathogen_load(&self) -> usize {
+        self.pathogen_count
+    }
+
+    /// The age of the infection when its pathogens first grew past the amount needed to become an
+    /// active case, or `None` if they haven't yet
+    pub fn became_active(&self) -> Option<&TimeUnit> {
+        self.became_active.as_ref()
+    }
+
+    /// The dose that someone infected by this infection would start with
+    pub fn transmitted_dose(&self) -> usize {
+        self.pathogen.initial_dose(self.pathogen_count)
+    }
+
+    /// Roughly how long until the infection recovers, which is zero once it's past due
+    pub fn remaining_time(&self) -> TimeUnit {
+        if self.recovered {
+            return Minutes(0);
+        }
+        self.predetermined_duration
+            .offset(TimeOffset::Earlier(self.infection_age.time_unit().clone()))
+    }
+
     pub fn infection_age(&self) -> &Age {
         &self.infection_age
     }
-}
 
-impl Update for Infection {
-    fn update_self(&mut self, delta_time: usize) {
+    /// Pauses the infection, so that updates leave it exactly as it is until it's thawed
+    pub fn freeze(&mut self) {
+        self.frozen = true;
+    }
+
+    /// Resumes the infection after it was frozen
+    pub fn thaw(&mut self) {
+        self.frozen = false;
+    }
+
+    pub fn frozen(&self) -> bool {
+        self.frozen
+    }
+
+    /// Every generation of an active infection has a chance to mutate the carried pathogen, which
+    /// grows with both how mutative the pathogen is and how fast it spreads within the host
+    fn evolve<R: Rng>(&mut self, minutes: usize, rng: &mut R) {
+        let generation = usize::from(PATHOGEN_GENERATION.as_minutes());
+        self.minutes_since_generation += minutes;
+        while self.minutes_since_generation >= generation {
+            self.minutes_since_generation -= generation;
+            let chance = self.pathogen.mutativity() * self.pathogen.internal_spread_rate();
+            if roll_with(chance, rng) {
+                self.pathogen = Arc::new(self.pathogen.mutate_with(rng));
+            }
+        }
+    }
+
+    /// Updates the infection like [Update::update], using `rng` for every roll so that seeded
+    /// updates can be reproduced
+    pub fn update_with_rng<R: Rng>(&mut self, delta_time: usize, rng: &mut R) {
+        if self.frozen {
+            return;
+        }
         let time_passed = tick_to_game_time_conversion(delta_time);
+        if self.recovered {
+            self.time_since_recovery = &self.time_since_recovery + time_passed;
+            return;
+        }
+        let minutes = usize::from(&time_passed);
         self.infection_age += time_passed;
+        if self.active_case() {
+            self.evolve(minutes, rng);
+        }
         if self.pathogen_count < self.pathogen.min_count_for_symptoms {
-            if roll(self.pathogen.internal_spread_rate) {
-                self.pathogen_count += (rand::thread_rng().gen_range::<f64, f64, f64>(0.2, 1.02)
-                    * self.pathogen_count as f64) as usize;
+            if roll_with(self.pathogen.internal_spread_rate, rng) {
+                self.pathogen_count += self
+                    .pathogen
+                    .within_host_growth()
+                    .growth(self.pathogen_count, rng);
+                if self.became_active.is_none() && self.active_case() {
+                    self.became_active = Some(self.infection_age.time_unit().clone());
+                }
             }
         } else {
             self.attempt_recover();
@@ -88,16 +317,30 @@ impl Update for Infection {
     }
 }
 
+impl Update for Infection {
+    fn update_self(&mut self, delta_time: usize) {
+        self.update_with_rng(delta_time, &mut rand::thread_rng());
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::collections::HashSet;
     use std::rc::Rc;
     use std::sync::Arc;
 
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
     use structure::graph::Graph;
+    use structure::time::Time;
+    use structure::time::TimeUnit::{Days, Minutes};
 
-    use crate::game::pathogen::infection::Infection;
-    use crate::game::pathogen::Pathogen;
+    use crate::game::pathogen::infection::{Infection, InfectionStage};
+    use crate::game::pathogen::symptoms::base::cheat::Undying;
+    use crate::game::pathogen::symptoms::Symp;
+    use crate::game::pathogen::types::{PathogenType, Virus};
+    use crate::game::pathogen::{Pathogen, WithinHostGrowth};
     use crate::game::Update;
 
     /// Checks if an infection will eventually become mature
@@ -119,4 +362,153 @@ mod test {
             infection.update(20);
         }
     }
+
+    #[test]
+    fn remaining_time_counts_down() {
+        let pathogen = Arc::new(Virus.default());
+        let mut infection = Infection::new(pathogen, 1.0);
+        assert_eq!(infection.remaining_time(), infection.predetermined_duration);
+
+        let mut previous = infection.remaining_time();
+        while !infection.recovered() {
+            infection.update(20 * 60);
+            let remaining = infection.remaining_time();
+            assert!(remaining < previous || remaining == Minutes(0));
+            previous = remaining;
+        }
+        assert_eq!(infection.remaining_time(), Minutes(0));
+    }
+
+    #[test]
+    fn chronic_infection_evolves() {
+        let mut p = Virus.default();
+        p.acquire_symptom(&Undying.get_symptom(), None);
+        p.set_mutation_weight(0, 1, 1.0).unwrap();
+        p.mutation = 0.0;
+        let original = p.clone();
+        let mut infection = Infection::new(Arc::new(p), 1.0);
+
+        for _ in 0..(90 * 24) {
+            if infection.get_pathogen().as_ref() != &original {
+                break;
+            }
+            infection.update(20 * 60);
+        }
+        assert!(!infection.recovered());
+        assert!(infection.get_pathogen().get_acquired().contains(&&1));
+    }
+
+    #[test]
+    fn frozen_infection_holds_still() {
+        let mut infection = Infection::new(Arc::new(Virus.default()), 1.0);
+        infection.update(20 * 60);
+        infection.freeze();
+
+        let age = infection.infection_age().time_unit().clone();
+        let load = infection.pathogen_load();
+        for _ in 0..100 {
+            infection.update(20 * 60);
+        }
+        assert_eq!(infection.infection_age().time_unit(), &age);
+        assert_eq!(infection.pathogen_load(), load);
+
+        infection.thaw();
+        infection.update(20 * 60);
+        assert!(*infection.infection_age().time_unit() > age);
+    }
+
+    #[test]
+    fn recovery_waits_for_minimum_infectious_period() {
+        // infections of this pathogen would last 5 to 15 minutes on their own
+        let mut pathogen = Pathogen::new(
+            "Test".to_string(),
+            0,
+            0.0,
+            10,
+            5,
+            Graph::new(),
+            HashSet::new(),
+        );
+        pathogen.set_minimum_infectious_period(Days(2));
+        let pathogen = Arc::new(pathogen);
+        let minimum = usize::from(Days(2).into_minutes());
+
+        for _ in 0..50 {
+            let mut infection = Infection::new(pathogen.clone(), 1.0);
+            while !infection.recovered() {
+                infection.update(20 * 5);
+            }
+            let lasted = usize::from(infection.infection_age().time_unit().as_minutes());
+            assert!(lasted >= minimum, "Recovered after {} minutes", lasted);
+            assert!(lasted < minimum + 10);
+        }
+    }
+
+    #[test]
+    fn seeded_infections_share_a_duration() {
+        let pathogen = Arc::new(Virus.default());
+        let seeded = |seed: u64| {
+            Infection::new_seeded(pathogen.clone(), 0.8, &mut StdRng::seed_from_u64(seed))
+                .predetermined_duration
+        };
+
+        for seed in 0..10 {
+            assert_eq!(seeded(seed), seeded(seed));
+        }
+        assert!(
+            (1..10).any(|seed| seeded(seed) != seeded(0)),
+            "Every seed gave the same duration"
+        );
+    }
+
+    #[test]
+    fn slow_growth_delays_active_cases() {
+        let minutes_to_active = |pathogen: Pathogen| {
+            let pathogen = Arc::new(pathogen);
+            let mut total = 0;
+            for _ in 0..20 {
+                let mut infection = Infection::new(pathogen.clone(), 1.0);
+                while infection.became_active().is_none() {
+                    infection.update(20);
+                }
+                assert!(infection.active_case());
+                total += usize::from(infection.became_active().unwrap().as_minutes());
+            }
+            total
+        };
+        let mut slow = Pathogen::default();
+        slow.set_within_host_growth(WithinHostGrowth::new(0.01, 0.05));
+
+        let default_minutes = minutes_to_active(Pathogen::default());
+        let slow_minutes = minutes_to_active(slow);
+        assert!(
+            slow_minutes > default_minutes * 5,
+            "Slow growth took {} minutes, default growth took {} minutes",
+            slow_minutes,
+            default_minutes
+        );
+    }
+
+    #[test]
+    fn stages_pass_in_order() {
+        let pathogen = Arc::new(Virus.default());
+        let mut infection = Infection::new(pathogen, 1.0);
+        let mut stages = vec![infection.stage()];
+        while !infection.recovered() {
+            infection.update(20 * 60);
+            if stages.last() != Some(&infection.stage()) {
+                stages.push(infection.stage());
+            }
+        }
+        assert_eq!(
+            stages,
+            vec![
+                InfectionStage::Incubating,
+                InfectionStage::Prodromal,
+                InfectionStage::Active,
+                InfectionStage::Resolving,
+                InfectionStage::Recovered,
+            ]
+        );
+    }
 }
@@ -1,24 +1,145 @@
 use std::borrow::{Borrow, BorrowMut};
 use std::collections::{HashMap, HashSet};
+use std::f64::consts::PI;
 use std::fmt::{Debug, Error, Formatter, Result};
+use std::hash::{Hash, Hasher};
 use std::io::Read;
 use std::rc::Rc;
 use std::sync::Arc;
 
+use rand::distributions::{Distribution, WeightedIndex};
 use rand::Rng;
 
-use structure::graph::Graph;
+use structure::graph::{Graph, GraphResult};
+use structure::graph::GraphError::{EdgeDoesNotExist, IdDoesNotExist, InvalidWeight};
 use structure::time::{Time, TimeUnit};
-use structure::time::TimeUnit::{Days, Hours};
+use structure::time::TimeUnit::{Days, Hours, Years};
 
+use crate::game::pathogen::infection::DEFAULT_INITIAL_DOSE;
 use crate::game::pathogen::symptoms::{Symptom, SymptomMap};
 use crate::game::population::Person;
-use crate::game::roll;
+use crate::game::roll_with;
 
 pub mod infection;
 pub mod symptoms;
 pub mod types;
 
+/// The default fraction that a seasonal catch chance rises above and falls below its average by
+pub const DEFAULT_SEASONAL_AMPLITUDE: f64 = 0.5;
+
+///
+/// Scales how easily a pathogen spreads over the course of a year, following a cosine that peaks
+/// on one day of the year and bottoms out half a year later
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeasonalProfile {
+    amplitude: f64,
+    peak_day: usize,
+}
+
+impl SeasonalProfile {
+    /// Creates a profile where the catch chance is `1 + amplitude` times its average on
+    /// `peak_day`, and `1 - amplitude` times its average half a year later
+    ///
+    /// # Panics
+    /// Panics if `amplitude` is not within the range [0, 1], or if `peak_day` is not a day of the
+    /// year
+    pub fn new(amplitude: f64, peak_day: usize) -> Self {
+        if !(0.0..=1.0).contains(&amplitude) {
+            panic!("Invalid seasonal amplitude: {}", amplitude);
+        }
+        if peak_day >= usize::from(Years(1).into_days()) {
+            panic!("Invalid peak day: {}", peak_day);
+        }
+        Self {
+            amplitude,
+            peak_day,
+        }
+    }
+
+    /// A profile that peaks in the middle of January, like most respiratory diseases in the
+    /// northern hemisphere
+    pub fn winter() -> Self {
+        Self::new(DEFAULT_SEASONAL_AMPLITUDE, 15)
+    }
+
+    pub fn amplitude(&self) -> f64 {
+        self.amplitude
+    }
+
+    /// The day of the year on which the catch chance is the highest
+    pub fn peak_day(&self) -> usize {
+        self.peak_day
+    }
+
+    /// The factor that the catch chance is multiplied by at `time_of_year`, which wraps around
+    /// if it's longer than a year
+    pub fn factor(&self, time_of_year: &TimeUnit) -> f64 {
+        let year = usize::from(Years(1).into_minutes()) as f64;
+        let minutes = usize::from(time_of_year.clone().into_minutes()) as f64 % year;
+        let peak = usize::from(Days(self.peak_day).into_minutes()) as f64;
+        1.0 + self.amplitude * (2.0 * PI * (minutes - peak) / year).cos()
+    }
+}
+
+/// The default range of the fraction that the pathogens in a host grow by each time they spread
+pub const DEFAULT_GROWTH_RANGE: (f64, f64) = (0.2, 1.02);
+
+///
+/// How the pathogens in a host multiply before the infection becomes an active case. Each time
+/// they spread, the amount of pathogens grows by a random fraction of itself within a range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WithinHostGrowth {
+    min_factor: f64,
+    max_factor: f64,
+}
+
+impl WithinHostGrowth {
+    /// Creates a growth where the amount of pathogens grows by between `min_factor` and
+    /// `max_factor` times itself each time they spread
+    ///
+    /// # Panics
+    /// Panics if `min_factor` is negative, or if `max_factor` is less than `min_factor`
+    pub fn new(min_factor: f64, max_factor: f64) -> Self {
+        if min_factor < 0.0 {
+            panic!("Invalid minimum growth factor: {}", min_factor);
+        }
+        if max_factor < min_factor {
+            panic!(
+                "Maximum growth factor {} is less than the minimum growth factor {}",
+                max_factor, min_factor
+            );
+        }
+        Self {
+            min_factor,
+            max_factor,
+        }
+    }
+
+    pub fn min_factor(&self) -> f64 {
+        self.min_factor
+    }
+
+    pub fn max_factor(&self) -> f64 {
+        self.max_factor
+    }
+
+    /// The amount that `count` pathogens grow by when they spread once, rolled with `rng`
+    pub fn growth<R: Rng>(&self, count: usize, rng: &mut R) -> usize {
+        let factor = if self.min_factor == self.max_factor {
+            self.min_factor
+        } else {
+            rng.gen_range(self.min_factor, self.max_factor)
+        };
+        (factor * count as f64) as usize
+    }
+}
+
+impl Default for WithinHostGrowth {
+    fn default() -> Self {
+        Self::new(DEFAULT_GROWTH_RANGE.0, DEFAULT_GROWTH_RANGE.1)
+    }
+}
+
 #[derive(Clone)]
 pub struct Pathogen {
     name: String,                                            // name of the pathogen
@@ -32,8 +153,37 @@ pub struct Pathogen {
     base_recovery_distance: usize, // in minutes, represents the base range for recovery
     symptoms_map: Graph<usize, f64, Arc<Symptom>>, // map of possible symptoms that a pathogen can have
     acquired_map: HashSet<usize>,                  // the set of acquired symptoms
-    on_recover: Vec<Arc<dyn Fn(&mut Person) + Send + Sync>>, // a vector of functions that affect a person after recovery
-    recover_function_position: HashMap<usize, usize>, // map of a symptoms ID to it's recovery function
+    on_recover: Vec<RecoveryEffect>, // functions that affect a person after recovery, in the order they run
+    upgrades: HashMap<usize, usize>, // map of a symptoms ID to the lower tier symptom it replaces
+    shedding_duration: usize,        // in minutes, how long a recovered person keeps shedding
+    shedding_infectivity: f64,       // fraction of the catch chance while shedding
+    dose_fraction: Option<f64>, // fraction of the infector's load that seeds a new infection
+    selection_candidates: usize, // mutated strains competing to be transmitted, 1 if neutral
+    establishment_chance: f64,   // chance that an exposure turns into an infection
+    detectability: f64,          // sum of the detectability of every acquired symptom
+    hospitalization_threshold: f64, // severity that has to be exceeded to need hospital care
+    minimum_infectious_period: usize, // in minutes, the shortest an infection can last
+    sequelae_chance: f64, // chance that recovering leaves lasting damage
+    sequelae_damage: f64, // fraction of the pre-existing condition factor that lasting damage takes
+    seasonality: Option<SeasonalProfile>, // None if the catch chance is the same all year
+    growth: WithinHostGrowth, // how the pathogens in a host multiply until they cause symptoms
+    post_mortem_infectious_period: usize, // in minutes, how long the body of someone it killed spreads it
+}
+
+/// A function that affects a person after they recover
+#[derive(Clone)]
+struct RecoveryEffect {
+    symptom: Option<String>, // the name of the symptom the effect came from
+    symptom_id: Option<usize>,
+    priority: i32,
+    function: Arc<dyn Fn(&mut Person) + Send + Sync>,
+}
+
+impl RecoveryEffect {
+    fn came_from(&self, symptom: &Symptom, symptom_id: Option<usize>) -> bool {
+        self.symptom.as_ref() == Some(symptom.get_name())
+            || (symptom_id.is_some() && self.symptom_id == symptom_id)
+    }
 }
 
 impl Debug for Pathogen {
@@ -43,6 +193,13 @@ impl Debug for Pathogen {
 }
 
 impl Pathogen {
+    /// The acquired symptom ids in ascending order, which together with the name identify a strain
+    fn sorted_acquired(&self) -> Vec<usize> {
+        let mut acquired: Vec<usize> = self.acquired_map.iter().cloned().collect();
+        acquired.sort();
+        acquired
+    }
+
     pub fn new<R>(
         name: String,
         min_count_for_symptoms: usize,
@@ -65,10 +222,23 @@ impl Pathogen {
             mutation: 1.0 - mutation,
             average_recovery_time, // in minutes
             base_recovery_distance,
+            upgrades: symptoms_map.get_upgrades().into_iter().collect(),
             symptoms_map: symptoms_map.get_map(),
             acquired_map: acquired.clone(),
             on_recover: Vec::new(),
-            recover_function_position: Default::default(),
+            shedding_duration: 0,
+            shedding_infectivity: 0.0,
+            dose_fraction: None,
+            selection_candidates: 1,
+            establishment_chance: 1.0,
+            detectability: 0.0,
+            hospitalization_threshold: 0.0,
+            minimum_infectious_period: 0,
+            sequelae_chance: 0.0,
+            sequelae_damage: 0.0,
+            seasonality: None,
+            growth: WithinHostGrowth::default(),
+            post_mortem_infectious_period: 0,
         };
 
         for ref node in acquired {
@@ -78,8 +248,11 @@ impl Pathogen {
         pathogen
     }
 
+    /// Gets the acquired symptom ids in ascending order
     pub fn get_acquired(&self) -> Vec<&usize> {
-        self.acquired_map.iter().map(|i| i).collect()
+        let mut output: Vec<&usize> = self.acquired_map.iter().collect();
+        output.sort();
+        output
     }
 
     /// Gets a list of the id of non acquired node ids and the weight for a mutation to get them
@@ -88,10 +261,13 @@ impl Pathogen {
         let mut output = Vec::new();
 
         for id in &acquired {
-            for to_id in self.symptoms_map.get_adjacent(**id) {
+            let node = match self.symptoms_map.resolve(**id) {
+                None => continue,
+                Some(node) => node,
+            };
+            for to_id in node.adjacent_sorted() {
                 if !acquired.contains(&to_id) {
-                    let weight = *self.symptoms_map.get_weight(**id, *to_id).unwrap();
-                    output.push((to_id, weight));
+                    output.push((to_id, *node.weight_to(to_id).unwrap()));
                 }
             }
         }
@@ -99,6 +275,80 @@ impl Pathogen {
         output
     }
 
+    /// Whether no mutation can acquire any more symptoms, which makes [Pathogen::mutate] unable to
+    /// do anything but lose symptoms
+    pub fn is_fully_evolved(&self) -> bool {
+        self.get_potential_gains().is_empty()
+    }
+
+    /// The amount of symptoms that the pathogen hasn't acquired but can still reach through any
+    /// amount of mutations
+    pub fn remaining_mutations(&self) -> usize {
+        let mut reached: HashSet<usize> = self.acquired_map.clone();
+        let mut frontier = self.get_acquired().into_iter().copied().collect::<Vec<_>>();
+        while let Some(id) = frontier.pop() {
+            for next in self.symptoms_map.get_adjacent(id) {
+                if reached.insert(*next) {
+                    frontier.push(*next);
+                }
+            }
+        }
+        reached.len() - self.acquired_map.len()
+    }
+
+    /// How genetically similar two strains are, in the range [0, 1]. Strains of pathogens with
+    /// different names are never similar, otherwise this is the fraction of their combined
+    /// symptoms that both of them have acquired.
+    pub fn similarity(&self, other: &Pathogen) -> f64 {
+        if self.name != other.name {
+            return 0.0;
+        }
+        let combined = self.acquired_map.union(&other.acquired_map).count();
+        if combined == 0 {
+            return 1.0;
+        }
+        let shared = self.acquired_map.intersection(&other.acquired_map).count();
+        shared as f64 / combined as f64
+    }
+
+    /// Writes the map of every symptom the pathogen can evolve in the Graphviz DOT format, labeled
+    /// with the symptom names and mutation weights
+    pub fn symptoms_to_dot(&self) -> String {
+        self.symptoms_map.to_dot(|symptom| symptom.get_name().clone())
+    }
+
+    /// Gets the chance that a mutation acquires the symptom `to_id` from the symptom `from_id`
+    pub fn mutation_weight(&self, from_id: usize, to_id: usize) -> Option<f64> {
+        self.symptoms_map.get_weight(from_id, to_id).copied()
+    }
+
+    /// Changes the chance that a mutation acquires the symptom `to_id` from the symptom `from_id`.
+    /// Only affects future mutations. Fails with `InvalidWeight` if `weight` is not within the
+    /// range [0, 1]
+    pub fn set_mutation_weight(
+        &mut self,
+        from_id: usize,
+        to_id: usize,
+        weight: f64,
+    ) -> GraphResult<usize> {
+        if !(0.0..=1.0).contains(&weight) {
+            return Err(InvalidWeight(from_id, to_id));
+        }
+        if !self.symptoms_map.contains_node(from_id) {
+            return Err(IdDoesNotExist(from_id));
+        }
+        if !self.symptoms_map.contains_node(to_id) {
+            return Err(IdDoesNotExist(to_id));
+        }
+        match self.symptoms_map.get_weight_mut(from_id, to_id) {
+            None => Err(EdgeDoesNotExist(from_id, to_id)),
+            Some(w) => {
+                *w = weight;
+                Ok(())
+            }
+        }
+    }
+
     fn sum_weights_onto_node(&self, id: &usize) -> f64 {
         let mut output = 0.0;
 
@@ -118,7 +368,7 @@ impl Pathogen {
         for id in &acquired {
             let acquired_leaf = self
                 .symptoms_map
-                .get_adjacent(**id)
+                .get_adjacent_sorted(**id)
                 .into_iter()
                 .map(|id| !acquired.contains(&id))
                 .fold(true, |b, item| b && item);
@@ -136,6 +386,7 @@ impl Pathogen {
         self.severity *= 1.0 - symptom.get_severity_increase() / 100.0;
         self.fatality *= 1.0 - symptom.get_fatality_increase() / 100.0;
         self.internal_spread_rate *= 1.0 - symptom.get_internal_spread_rate_increase() / 100.0;
+        self.detectability += symptom.get_detectability();
         if let Some(base) = symptom.get_duration_change() {
             if base.is_infinite() {
                 self.average_recovery_time = std::usize::MAX;
@@ -147,10 +398,14 @@ impl Pathogen {
             self.base_recovery_distance = (self.base_recovery_distance as f64 * *spread) as usize;
         }
         if let Some(function) = symptom.get_recovery_effect() {
-            let index = self.on_recover.len();
-            self.on_recover.push((*function).clone());
-            if let Some(id) = symptom_id {
-                self.recover_function_position.insert(id, index);
+            // a symptom's recovery function only runs once, no matter how often it's acquired
+            if !self.on_recover.iter().any(|e| e.came_from(symptom, symptom_id)) {
+                self.insert_recovery_effect(RecoveryEffect {
+                    symptom: Some(symptom.get_name().clone()),
+                    symptom_id,
+                    priority: symptom.get_recovery_priority(),
+                    function: function.clone(),
+                });
             }
         }
         symptom.additional_effect()
@@ -161,6 +416,7 @@ impl Pathogen {
         self.severity /= 1.0 - symptom.get_severity_increase() / 100.0;
         self.fatality /= 1.0 - symptom.get_fatality_increase() / 100.0;
         self.internal_spread_rate /= 1.0 - symptom.get_internal_spread_rate_increase() / 100.0;
+        self.detectability -= symptom.get_detectability();
 
         if let Some(base) = symptom.get_duration_change() {
             self.average_recovery_time = (self.average_recovery_time as f64 / *base) as usize;
@@ -169,12 +425,7 @@ impl Pathogen {
             self.base_recovery_distance = (self.base_recovery_distance as f64 / *spread) as usize;
         }
 
-        if let Some(id) = symptom_id {
-            if self.recover_function_position.contains_key(&id) {
-                self.on_recover.remove(id);
-                self.recover_function_position.remove(&id);
-            }
-        }
+        self.on_recover.retain(|e| !e.came_from(symptom, symptom_id));
     }
 
     pub fn name(&self) -> &String {
@@ -185,6 +436,25 @@ impl Pathogen {
         1.0 - self.catch_chance
     }
 
+    /// The catch chance at `time_of_year`, scaled by the pathogen's [SeasonalProfile] if it has
+    /// one
+    pub fn catch_chance_at(&self, time_of_year: &TimeUnit) -> f64 {
+        match &self.seasonality {
+            Some(profile) => f64::min(1.0, self.catch_chance() * profile.factor(time_of_year)),
+            None => self.catch_chance(),
+        }
+    }
+
+    /// How the catch chance changes over the year, or `None` if it's the same all year
+    pub fn seasonality(&self) -> Option<&SeasonalProfile> {
+        self.seasonality.as_ref()
+    }
+
+    /// Sets how the catch chance changes over the year
+    pub fn set_seasonality(&mut self, seasonality: Option<SeasonalProfile>) {
+        self.seasonality = seasonality;
+    }
+
     pub fn severity(&self) -> f64 {
         1.0 - self.severity
     }
@@ -193,50 +463,308 @@ impl Pathogen {
         1.0 - self.fatality
     }
 
+    /// How much the acquired symptoms make an infected person stand out, as the sum of each
+    /// symptom's detectability
+    pub fn detectability(&self) -> f64 {
+        self.detectability
+    }
+
+    /// The chance that the pathogen mutates each generation
+    pub fn mutativity(&self) -> f64 {
+        1.0 - self.mutation
+    }
+
     pub fn average_recovery_time(&self) -> usize {
         self.average_recovery_time
     }
 
+    /// Scores how well the pathogen would do through natural selection, as the amount of days it
+    /// spends spreading in an average host that it doesn't kill first, scaled by its catch
+    /// chance. Pathogens that spread easily and for a long time without killing are the fittest.
+    pub fn fitness(&self) -> f64 {
+        let minutes = self.average_recovery_time as f64;
+        // the chance that a host survives every fatality roll while infected
+        let survival = (1.0 - self.fatality()).powf(minutes);
+        self.catch_chance() * (minutes / (60.0 * 24.0)) * survival
+    }
+
+    /// How far, in minutes, the duration of an infection can be from the average recovery time.
+    /// Symptoms can widen it as much as they like, but it always stays below the average so that
+    /// every infection still lasts for some time.
     pub fn base_recovery_distance(&self) -> usize {
-        self.base_recovery_distance
+        usize::min(
+            self.base_recovery_distance,
+            self.average_recovery_time.saturating_sub(1),
+        )
     }
 
     pub fn internal_spread_rate(&self) -> f64 {
         1.0 - self.internal_spread_rate
     }
 
+    /// The amount of pathogens a host has to carry for the infection to become an active case
+    pub fn min_count_for_symptoms(&self) -> usize {
+        self.min_count_for_symptoms
+    }
+
+    pub fn within_host_growth(&self) -> &WithinHostGrowth {
+        &self.growth
+    }
+
+    /// Changes how the pathogens in a host multiply, which speeds up or slows down how long it
+    /// takes an infection to become an active case
+    pub fn set_within_host_growth(&mut self, growth: WithinHostGrowth) {
+        self.growth = growth;
+    }
+
+    /// How long, in minutes, a recovered person keeps shedding the pathogen
+    pub fn shedding_duration(&self) -> usize {
+        self.shedding_duration
+    }
+
+    /// The fraction of the catch chance that a recovered person spreads with while shedding
+    pub fn shedding_infectivity(&self) -> f64 {
+        self.shedding_infectivity
+    }
+
+    /// Lets recovered people remain mildly infectious for `duration` after recovering
+    ///
+    /// # Panics
+    /// Panics if `infectivity` is not within the range [0, 1]
+    pub fn set_shedding(&mut self, duration: TimeUnit, infectivity: f64) {
+        if !(0.0..=1.0).contains(&infectivity) {
+            panic!("Invalid shedding infectivity: {}", infectivity);
+        }
+        self.shedding_duration = usize::from(duration.into_minutes());
+        self.shedding_infectivity = infectivity;
+    }
+
+    /// How long, in minutes, the body of someone who died while infected keeps spreading the
+    /// pathogen
+    pub fn post_mortem_infectious_period(&self) -> usize {
+        self.post_mortem_infectious_period
+    }
+
+    /// Lets the bodies of people who died while infected keep spreading the pathogen for `period`
+    /// after their death, such as through contact at funerals
+    pub fn set_post_mortem_infectious_period(&mut self, period: TimeUnit) {
+        self.post_mortem_infectious_period = usize::from(period.into_minutes());
+    }
+
+    /// How long, in minutes, every infection lasts at the very least before it can recover
+    pub fn minimum_infectious_period(&self) -> usize {
+        self.minimum_infectious_period
+    }
+
+    /// Keeps every infection from recovering before `period` has passed, no matter how short its
+    /// sampled duration was
+    pub fn set_minimum_infectious_period(&mut self, period: TimeUnit) {
+        self.minimum_infectious_period = usize::from(period.into_minutes());
+    }
+
+    /// The fraction of an infector's pathogen load that a new infection starts with, or `None`
+    /// if every infection starts with the default dose
+    pub fn dose_fraction(&self) -> Option<f64> {
+        self.dose_fraction
+    }
+
+    /// Makes new infections start with a fraction of their infector's pathogen load, so contact
+    /// with a high load seeds a more aggressive infection
+    ///
+    /// # Panics
+    /// Panics if `fraction` is not within the range (0, 1]
+    pub fn set_dose_fraction(&mut self, fraction: Option<f64>) {
+        if let Some(fraction) = fraction {
+            if fraction <= 0.0 || fraction > 1.0 {
+                panic!("Invalid dose fraction: {}", fraction);
+            }
+        }
+        self.dose_fraction = fraction;
+    }
+
+    /// The chance that a susceptible person who is exposed to the pathogen gets infected
+    pub fn establishment_chance(&self) -> f64 {
+        self.establishment_chance
+    }
+
+    /// Sets the chance that an exposure turns into an infection, where the rest of exposures
+    /// fizzle out and leave the person susceptible
+    ///
+    /// # Panics
+    /// Panics if `chance` is not within the range (0, 1]
+    pub fn set_establishment_chance(&mut self, chance: f64) {
+        if chance <= 0.0 || chance > 1.0 {
+            panic!("Invalid establishment chance: {}", chance);
+        }
+        self.establishment_chance = chance;
+    }
+
+    /// The severity that has to be exceeded before the pathogen can make anyone need hospital care
+    pub fn hospitalization_threshold(&self) -> f64 {
+        self.hospitalization_threshold
+    }
+
+    /// Sets the severity that has to be exceeded before the pathogen can make anyone need
+    /// hospital care, so mild pathogens don't create any hospital demand
+    ///
+    /// # Panics
+    /// Panics if `threshold` is not within the range [0, 1]
+    pub fn set_hospitalization_threshold(&mut self, threshold: f64) {
+        if !(0.0..=1.0).contains(&threshold) {
+            panic!("Invalid hospitalization threshold: {}", threshold);
+        }
+        self.hospitalization_threshold = threshold;
+    }
+
+    /// The chance that someone who recovers is left with lasting damage
+    pub fn sequelae_chance(&self) -> f64 {
+        self.sequelae_chance
+    }
+
+    /// The fraction of their pre-existing condition factor that lasting damage takes from someone
+    pub fn sequelae_damage(&self) -> f64 {
+        self.sequelae_damage
+    }
+
+    /// Makes recovering leave lasting damage with a chance of `chance`, permanently lowering the
+    /// pre-existing condition factor of the person, and with it their max health, by `damage`
+    ///
+    /// # Panics
+    /// Panics if `chance` or `damage` is not within the range [0, 1]
+    pub fn set_sequelae(&mut self, chance: f64, damage: f64) {
+        if !(0.0..=1.0).contains(&chance) {
+            panic!("Invalid sequelae chance: {}", chance);
+        }
+        if !(0.0..=1.0).contains(&damage) {
+            panic!("Invalid sequelae damage: {}", damage);
+        }
+        self.sequelae_chance = chance;
+        self.sequelae_damage = damage;
+    }
+
+    /// The amount of mutated strains that compete to be transmitted
+    pub fn selection_candidates(&self) -> usize {
+        self.selection_candidates
+    }
+
+    /// Makes `candidates` mutated strains compete at every transmission, where fitter strains are
+    /// more likely to be the one transmitted. A single candidate is neutral mutation.
+    ///
+    /// # Panics
+    /// Panics if `candidates` is 0
+    pub fn set_selection_candidates(&mut self, candidates: usize) {
+        if candidates == 0 {
+            panic!("Invalid amount of selection candidates: {}", candidates);
+        }
+        self.selection_candidates = candidates;
+    }
+
+    /// The amount of pathogens that an infection caught from a host with `infector_load`
+    /// pathogens starts with
+    pub fn initial_dose(&self, infector_load: usize) -> usize {
+        match self.dose_fraction {
+            None => DEFAULT_INITIAL_DOSE,
+            Some(fraction) => usize::max(1, (infector_load as f64 * fraction) as usize),
+        }
+    }
+
     fn add_recovery_symptom<F>(&mut self, function: F)
     where
         F: 'static + Fn(&mut Person) + Send + Sync,
     {
-        self.on_recover.push(Arc::new(function))
+        self.insert_recovery_effect(RecoveryEffect {
+            symptom: None,
+            symptom_id: None,
+            priority: 0,
+            function: Arc::new(function),
+        })
+    }
+
+    /// Keeps the recovery effects sorted from highest to lowest priority, after any effects with
+    /// the same priority
+    fn insert_recovery_effect(&mut self, effect: RecoveryEffect) {
+        let index = self
+            .on_recover
+            .iter()
+            .position(|e| e.priority < effect.priority)
+            .unwrap_or(self.on_recover.len());
+        self.on_recover.insert(index, effect);
     }
 
     pub fn perform_recovery(&self, person: &mut Person) {
-        for funcs in &self.on_recover {
-            funcs(person)
+        self.perform_recovery_with(person, &mut rand::thread_rng())
+    }
+
+    /// Performs the recovery like [Pathogen::perform_recovery], using `rng` for the sequelae roll
+    pub fn perform_recovery_with<R: Rng>(&self, person: &mut Person, rng: &mut R) {
+        if self.sequelae_chance > 0.0 && roll_with(self.sequelae_chance, rng) {
+            person.suffer_lasting_damage(self.sequelae_damage);
+        }
+        for effect in &self.on_recover {
+            (effect.function)(person)
         }
     }
 
     pub fn mutate(&self) -> Self {
+        self.mutate_with(&mut rand::thread_rng())
+    }
+
+    /// Gets the strain that is transmitted to a new host. Each candidate strain is a mutation of
+    /// this one, and a candidate is chosen with a chance proportional to its fitness.
+    pub fn select_mutation_with<R: Rng>(&self, rng: &mut R) -> Self {
+        if self.selection_candidates == 1 {
+            return self.mutate_with(rng);
+        }
+        let mut candidates = (0..self.selection_candidates)
+            .map(|_| self.mutate_with(rng))
+            .collect::<Vec<_>>();
+        let weights = candidates.iter().map(|c| {
+            let fitness = c.fitness();
+            if fitness.is_finite() {
+                fitness
+            } else {
+                0.0
+            }
+        });
+        // if no candidate is fit at all, they're all equally likely
+        let index = match WeightedIndex::new(weights) {
+            Ok(distribution) => distribution.sample(rng),
+            Err(_) => rng.gen_range(0, candidates.len()),
+        };
+        candidates.swap_remove(index)
+    }
+
+    /// Mutates the pathogen, using `rng` for every roll so that a seeded rng gives a
+    /// reproducible mutation
+    pub fn mutate_with<R: Rng>(&self, rng: &mut R) -> Self {
         let mut next_pathogen = self.clone();
 
         let potential_gains = self.get_potential_gains();
 
         for (id, chance) in potential_gains {
-            if roll(chance) && !next_pathogen.acquired_map.contains(id) {
+            if rng.gen_bool(chance) && !next_pathogen.acquired_map.contains(id) {
                 next_pathogen.acquire_symptom(
                     self.symptoms_map.get(id).unwrap().clone().borrow_mut(),
                     Some(*id),
                 );
                 next_pathogen.acquired_map.insert(*id);
+
+                if let Some(replaced) = self.upgrades.get(id) {
+                    if next_pathogen.acquired_map.contains(replaced) {
+                        next_pathogen.remove_symptom(
+                            self.symptoms_map.get(replaced).unwrap(),
+                            Some(*replaced),
+                        );
+                        next_pathogen.acquired_map.remove(replaced);
+                    }
+                }
             }
         }
 
         let potential_losses = self.get_potential_losses();
 
         for (id, chance) in potential_losses {
-            if roll(chance) && next_pathogen.acquired_map.contains(id) {
+            if rng.gen_bool(chance) && next_pathogen.acquired_map.contains(id) {
                 next_pathogen.remove_symptom(
                     self.symptoms_map.get(id).unwrap().clone().borrow_mut(),
                     Some(*id),
@@ -249,6 +777,22 @@ impl Pathogen {
     }
 }
 
+/// Pathogens are genetically identical if they have the same name and acquired symptoms
+impl PartialEq for Pathogen {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.acquired_map == other.acquired_map
+    }
+}
+
+impl Eq for Pathogen {}
+
+impl Hash for Pathogen {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.sorted_acquired().hash(state);
+    }
+}
+
 impl Default for Pathogen {
     fn default() -> Self {
         Pathogen::new(
@@ -265,15 +809,39 @@ impl Default for Pathogen {
 
 #[cfg(test)]
 mod test {
+    use std::collections::HashSet;
     use std::sync::{Arc, Mutex};
 
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use structure::time::Time;
+    use structure::time::TimeUnit::Days;
+
     use crate::game::Age;
-    use crate::game::pathogen::Pathogen;
-    use crate::game::pathogen::symptoms::Symptom;
+    use crate::game::pathogen::{Pathogen, SeasonalProfile};
+    use crate::game::pathogen::symptoms::base::cheat::{CustomCatchChance, CustomFatality};
+    use crate::game::pathogen::symptoms::base::RunnyNose;
+    use crate::game::pathogen::symptoms::{Symp, Symptom, SymptomMapBuilder};
     use crate::game::pathogen::types::{PathogenType, Virus};
     use crate::game::population::Person;
     use crate::game::population::Sex::Male;
 
+    #[test]
+    fn seasonal_catch_chance_peaks_in_winter() {
+        let mut p = Virus.create_pathogen("Test", 0);
+        p.acquire_symptom(&CustomCatchChance(50.0).get_symptom(), None);
+        let average = p.catch_chance();
+        assert_eq!(p.catch_chance_at(&Days(200)), average);
+
+        p.set_seasonality(Some(SeasonalProfile::winter()));
+        let peak = p.catch_chance_at(&Days(15));
+        let trough = p.catch_chance_at(&Days(15 + 365 / 2));
+        assert!(peak > average, "Peak was {}, average {}", peak, average);
+        assert!(trough < average, "Trough was {}, average {}", trough, average);
+        assert!((p.catch_chance_at(&Days(15 + 365)) - peak).abs() < 1e-9);
+    }
+
     #[test]
     fn add_symptom_increases_catch_chance() {
         let mut p = Pathogen::default();
@@ -324,6 +892,105 @@ mod test {
         assert_eq!(p.catch_chance(), catch);
     }
 
+    #[test]
+    fn zero_mutation_weight_never_acquired() {
+        let mut p = Virus.default();
+        assert_eq!(p.mutation_weight(0, 1), Some(0.5));
+        p.set_mutation_weight(0, 1, 0.0).unwrap();
+        assert_eq!(p.mutation_weight(0, 1), Some(0.0));
+        assert!(p.set_mutation_weight(1, 0, 0.0).is_err());
+        assert!(p.set_mutation_weight(0, 1, 1.5).is_err());
+        assert_eq!(p.mutation_weight(0, 1), Some(0.0));
+
+        for _ in 0..1000 {
+            p = p.mutate();
+            assert!(!p.get_acquired().contains(&&1));
+        }
+    }
+
+    #[test]
+    fn upgrade_replaces_lower_tier() {
+        let mut p = Virus.default();
+        p.set_mutation_weight(0, 1, 1.0).unwrap();
+        p.set_mutation_weight(1, 2, 1.0).unwrap();
+        p.set_mutation_weight(2, 3, 0.0).unwrap();
+
+        p = p.mutate();
+        assert!(p.get_acquired().contains(&&1), "Should have acquired Cough 1");
+
+        p = p.mutate();
+        assert!(p.get_acquired().contains(&&2), "Should have acquired Cough 2");
+        assert!(
+            !p.get_acquired().contains(&&1),
+            "Cough 2 should have replaced Cough 1"
+        );
+    }
+
+    #[test]
+    fn small_maps_evolve_fully() {
+        let mut builder = SymptomMapBuilder::new();
+        let mut entry = builder.add(RunnyNose.get_symptom());
+        let root = entry.node();
+        entry
+            .next_symptom(CustomCatchChance(10.0).get_symptom(), 0.5)
+            .next_symptom(CustomFatality(0.1).get_symptom(), 0.5);
+        let mut p = Pathogen::new(
+            "Test".to_string(),
+            0,
+            0.0,
+            2 * 24 * 60,
+            0,
+            builder,
+            vec![root].into_iter().collect(),
+        );
+        assert_eq!(p.remaining_mutations(), 2);
+        assert!(!p.is_fully_evolved());
+
+        let mut rng = StdRng::seed_from_u64(2189);
+        let mut mutations = 0;
+        while !p.is_fully_evolved() {
+            p = p.mutate_with(&mut rng);
+            mutations += 1;
+            assert!(mutations < 1000, "Never evolved fully");
+        }
+        assert_eq!(p.remaining_mutations(), 0);
+        assert_eq!(p.get_acquired().len(), 3);
+    }
+
+    /// Mutates the default virus a few times with a seeded rng and gets the acquired symptoms
+    fn seeded_mutations(seed: u64) -> Vec<usize> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut p = Virus.default();
+        for _ in 0..10 {
+            p = p.mutate_with(&mut rng);
+        }
+        p.get_acquired().into_iter().cloned().collect()
+    }
+
+    #[test]
+    fn seeded_mutation_is_deterministic() {
+        let acquired = seeded_mutations(5);
+        assert_eq!(acquired, seeded_mutations(5));
+        assert_eq!(acquired, vec![0, 2]);
+    }
+
+    #[test]
+    fn strains_compare_by_genetics() {
+        let mut parent = Virus.default();
+        assert_eq!(parent.clone(), parent);
+
+        parent.set_mutation_weight(0, 1, 1.0).unwrap();
+        let child = parent.mutate();
+        assert_ne!(child, parent);
+
+        let mut strains = HashSet::new();
+        strains.insert(parent.clone());
+        strains.insert(parent.clone());
+        strains.insert(child.clone());
+        assert_eq!(strains.len(), 2);
+        assert!(strains.contains(&child));
+    }
+
     #[test]
     fn add_and_remove_on_recover_function() {
         let mut p = Pathogen::default();
@@ -363,4 +1030,106 @@ mod test {
             "Problem with recovery functions acting on objects"
         );
     }
+
+    #[test]
+    fn recovery_effects_run_once_by_priority() {
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let recovery_symptom = |name: &'static str, priority: i32| {
+            let order = order.clone();
+            let function: Arc<dyn Fn(&mut Person) + Send + Sync> =
+                Arc::new(move |_| order.lock().unwrap().push(name));
+            Symptom::new(
+                name.to_string(),
+                "Test".to_string(),
+                1.0,
+                1.0,
+                1.0,
+                1.0,
+                None,
+                None,
+                None,
+                Some(&function),
+            )
+            .with_recovery_priority(priority)
+        };
+
+        let mut p = Pathogen::default();
+        let restore = recovery_symptom("Restore", 0);
+        p.acquire_symptom(&restore, None);
+        p.acquire_symptom(&recovery_symptom("Remove Immunity", 5), None);
+        p.acquire_symptom(&restore, None);
+        p.acquire_symptom(&recovery_symptom("Restore", 0), None);
+
+        let mut person = Person::new(0, Age::new(17, 0, 0), Male, 1.00);
+        p.perform_recovery(&mut person);
+        assert_eq!(*order.lock().unwrap(), vec!["Remove Immunity", "Restore"]);
+
+        p.remove_symptom(&restore, None);
+        order.lock().unwrap().clear();
+        p.perform_recovery(&mut person);
+        assert_eq!(*order.lock().unwrap(), vec!["Remove Immunity"]);
+    }
+
+    #[test]
+    fn lethal_strains_are_less_fit() {
+        let mut mild = Virus.create_pathogen("Test", 0);
+        mild.acquire_symptom(&CustomCatchChance(90.0).get_symptom(), None);
+        let mut lethal = mild.clone();
+        lethal.acquire_symptom(&CustomFatality(50.0).get_symptom(), None);
+        assert_eq!(mild.catch_chance(), lethal.catch_chance());
+
+        assert!(mild.fitness() > 0.0);
+        assert!(
+            mild.fitness() > lethal.fitness(),
+            "Mild strain fitness was {}, lethal strain fitness was {}",
+            mild.fitness(),
+            lethal.fitness()
+        );
+    }
+
+    /// Evolves 100 lineages of a pathogen that can become more contagious or more lethal through
+    /// a chain of transmissions, and gets the mean fitness of the final strains
+    fn evolved_fitness(candidates: usize) -> f64 {
+        let mut builder = SymptomMapBuilder::new();
+        let mut entry = builder.add(RunnyNose.get_symptom());
+        let root = entry.node();
+        entry.add_next_symptoms(vec![
+            (CustomCatchChance(50.0).get_symptom(), 0.2),
+            (CustomFatality(0.1).get_symptom(), 0.2),
+        ]);
+        let mut pathogen = Pathogen::new(
+            "Test".to_string(),
+            1_000_000,
+            0.05,
+            usize::from(Days(8).into_minutes()),
+            usize::from(Days(3).into_minutes()),
+            builder,
+            vec![root].into_iter().collect(),
+        );
+        pathogen.set_selection_candidates(candidates);
+
+        let mut rng = StdRng::seed_from_u64(17);
+        let total = (0..100)
+            .map(|_| {
+                let mut strain = pathogen.clone();
+                for _ in 0..30 {
+                    strain = strain.select_mutation_with(&mut rng);
+                }
+                strain.fitness()
+            })
+            .sum::<f64>();
+        total / 100.0
+    }
+
+    #[test]
+    fn selection_favors_fitter_strains() {
+        let neutral = evolved_fitness(1);
+        let selected = evolved_fitness(5);
+        assert!(
+            selected > neutral,
+            "Mean fitness under selection was {}, under neutral mutation was {}",
+            selected,
+            neutral
+        );
+    }
 }
@@ -21,6 +21,8 @@ pub struct Symptom {
     spread_change: Option<f64>,
     additional_effect: Option<fn()>,
     recovery_function: Option<Arc<dyn Fn(&mut Person) + Send + Sync>>,
+    recovery_priority: i32, // recovery functions with higher priorities run first
+    detectability: f64,     // how much the symptom makes an infected person stand out
 }
 
 impl Symptom {
@@ -137,9 +139,19 @@ impl Symptom {
                 Some(f) => Some(f),
             },
             recovery_function: recovery_function.map(|f| f.clone()),
+            recovery_priority: 0,
+            detectability: 0.0,
         }
     }
 
+    /// Sets the priority of the recovery function, where recovery functions with higher
+    /// priorities run first. Recovery functions with the same priority run in the order their
+    /// symptoms were acquired.
+    pub fn with_recovery_priority(mut self, priority: i32) -> Self {
+        self.recovery_priority = priority;
+        self
+    }
+
     pub fn get_name(&self) -> &String {
         &self.name
     }
@@ -186,6 +198,27 @@ impl Symptom {
     pub fn get_recovery_effect(&self) -> &Option<Arc<dyn Fn(&mut Person) + Send + Sync>> {
         &self.recovery_function
     }
+
+    pub fn get_recovery_priority(&self) -> i32 {
+        self.recovery_priority
+    }
+
+    /// Sets how much the symptom adds to the chance that an infected person is detected, such as
+    /// a visible cough
+    ///
+    /// # Panics
+    /// Panics if `detectability` is negative
+    pub fn with_detectability(mut self, detectability: f64) -> Self {
+        if detectability < 0.0 {
+            panic!("Invalid detectability: {}", detectability);
+        }
+        self.detectability = detectability;
+        self
+    }
+
+    pub fn get_detectability(&self) -> f64 {
+        self.detectability
+    }
 }
 
 pub trait Symp {
@@ -195,6 +228,11 @@ pub trait Symp {
 pub trait SymptomMap {
     fn get_map(self) -> Graph<usize, f64, Arc<Symptom>>;
 
+    /// Gets pairs of `(upgrade, replaced)` symptom ids, where acquiring `upgrade` replaces `replaced`
+    fn get_upgrades(&self) -> Vec<(usize, usize)> {
+        Vec::new()
+    }
+
     fn new() -> Graph<usize, f64, Arc<Symptom>> {
         Graph::new()
     }
@@ -217,6 +255,7 @@ impl Debug for Symptom {
 pub struct SymptomMapBuilder {
     symptoms_map: Graph<usize, f64, Arc<Symptom>>,
     symptoms: Vec<Arc<Symptom>>,
+    upgrades: Vec<(usize, usize)>,
     next_id: usize,
 }
 
@@ -225,6 +264,7 @@ impl SymptomMapBuilder {
         Self {
             symptoms_map: Graph::new(),
             symptoms: vec![],
+            upgrades: vec![],
             next_id: 0,
         }
     }
@@ -262,12 +302,29 @@ impl SymptomMapBuilder {
     ) -> GraphResult<usize> {
         self.symptoms_map.add_edge(id1, id2, mutation_chance)
     }
+
+    /// Adds a mutation from `id1` to `id2` where `id2` is a higher tier of `id1`, so acquiring
+    /// `id2` removes `id1`
+    pub fn add_upgrade(
+        &mut self,
+        id1: usize,
+        id2: usize,
+        mutation_chance: f64,
+    ) -> GraphResult<usize> {
+        self.add_next_symptom(id1, id2, mutation_chance)?;
+        self.upgrades.push((id2, id1));
+        Ok(())
+    }
 }
 
 impl SymptomMap for SymptomMapBuilder {
     fn get_map(self) -> Graph<usize, f64, Arc<Symptom>> {
         self.symptoms_map
     }
+
+    fn get_upgrades(&self) -> Vec<(usize, usize)> {
+        self.upgrades.clone()
+    }
 }
 
 pub struct SymptomMapBuilderEntry<'a> {
@@ -299,6 +356,42 @@ impl<'a> SymptomMapBuilderEntry<'a> {
         output
     }
 
+    /// Adds a higher tier of this entry's symptom, which replaces it once acquired
+    pub fn upgrade_symptom(
+        &mut self,
+        symptom: Symptom,
+        mutation_chance: f64,
+    ) -> SymptomMapBuilderEntry {
+        let output = self.map_builder.add(symptom);
+        let id1 = self.node;
+        let id2 = output.node;
+        output
+            .map_builder
+            .add_upgrade(id1, id2, mutation_chance)
+            .expect("Should not fail");
+        output
+    }
+
+    /// Adds a symptom that this entry's symptom can mutate into, and continues building from the
+    /// new symptom inside of `build`. Returns this entry, so that more branches can be chained.
+    ///
+    /// ```ignore
+    /// builder
+    ///     .add(RunnyNose.get_symptom())
+    ///     .branch(Cough(1).get_symptom(), 0.5, |cough| {
+    ///         cough.branch(Cough(2).get_symptom(), 0.02, |_| {});
+    ///     })
+    ///     .branch(CustomSeverity(5.0).get_symptom(), 0.1, |_| {});
+    /// ```
+    pub fn branch<F>(&mut self, symptom: Symptom, mutation_chance: f64, build: F) -> &mut Self
+    where
+        F: FnOnce(&mut SymptomMapBuilderEntry),
+    {
+        let mut next = self.next_symptom(symptom, mutation_chance);
+        build(&mut next);
+        self
+    }
+
     pub fn add_next_symptoms(&mut self, symptoms: Vec<(Symptom, f64)>) -> Vec<usize> {
         let mut output = Vec::new();
         for (symptom, mutation_chance) in symptoms {
@@ -520,6 +613,33 @@ pub mod base {
                 None,
                 None,
             )
+            .with_detectability(0.05)
+        }
+    }
+
+    /// Makes the course of an infection vary a lot more between people, multiplying how far its
+    /// duration can be from the average by the given factor
+    ///
+    /// # Panics
+    /// Getting the symptom panics if the factor is less than 1
+    pub struct VariableCourse(pub f64);
+    impl Symp for VariableCourse {
+        fn get_symptom(&self) -> Symptom {
+            if self.0 < 1.0 {
+                panic!("Invalid course variance factor: {}", self.0);
+            }
+            Symptom::new(
+                format!("Variable Course {}", self.0),
+                "Nobody can say how long it will last".to_string(),
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+                None,
+                Some(self.0),
+                None,
+                None,
+            )
         }
     }
 
@@ -538,6 +658,7 @@ pub mod base {
                 None,
                 None,
             )
+            .with_detectability(0.1 * self.0 as f64)
         }
     }
 }
@@ -553,9 +674,13 @@ mod test {
 
     use rand::thread_rng;
 
+    use structure::time::Time;
+
     use crate::game::{Age, Update};
-    use crate::game::pathogen::symptoms::base::cheat::NeverImmune;
-    use crate::game::pathogen::symptoms::Symp;
+    use crate::game::pathogen::symptoms::base::cheat::{CustomSeverity, NeverImmune};
+    use crate::game::pathogen::infection::Infection;
+    use crate::game::pathogen::symptoms::base::{Cough, RunnyNose, VariableCourse};
+    use crate::game::pathogen::symptoms::{Symp, SymptomMap, SymptomMapBuilder};
     use crate::game::pathogen::types::{PathogenType, Virus};
     use crate::game::population::Person;
     use crate::game::population::Sex::Male;
@@ -599,4 +724,68 @@ mod test {
             panic!("Thread errored out when it should not have")
         }
     }
+
+    #[test]
+    fn branching_symptom_tree() {
+        let mut builder = SymptomMapBuilder::new();
+        builder
+            .add(RunnyNose.get_symptom())
+            .branch(Cough(1).get_symptom(), 0.5, |cough| {
+                cough
+                    .branch(Cough(2).get_symptom(), 0.2, |_| {})
+                    .branch(CustomSeverity(5.0).get_symptom(), 0.1, |_| {});
+            })
+            .branch(CustomSeverity(10.0).get_symptom(), 0.3, |severe| {
+                severe.branch(Cough(3).get_symptom(), 0.05, |_| {});
+            });
+
+        let map = builder.get_map();
+        assert_eq!(map.len(), 6);
+        assert_eq!(map.edge_count(), 5);
+        // ids are given out in the order the symptoms were added
+        let edges = vec![(0, 1, 0.5), (1, 2, 0.2), (1, 3, 0.1), (0, 4, 0.3), (4, 5, 0.05)];
+        for (u, v, weight) in edges {
+            assert_eq!(map.get_weight(u, v), Some(&weight), "{} -> {}", u, v);
+        }
+        assert_eq!(map.get(&5).unwrap().get_name(), Cough(3).get_symptom().get_name());
+    }
+
+    #[test]
+    fn variable_course_widens_recovery_times() {
+        // the shortest and longest of many sampled durations, in minutes
+        let duration_range = |factor: Option<f64>| {
+            let mut p = Virus.create_pathogen("Test", 0);
+            if let Some(factor) = factor {
+                p.acquire_symptom(&VariableCourse(factor).get_symptom(), None);
+            }
+            let pathogen = Arc::new(p);
+            let durations = (0..500)
+                .map(|_| {
+                    let infection = Infection::new(pathogen.clone(), 0.9);
+                    usize::from(infection.remaining_time().as_minutes())
+                })
+                .collect::<Vec<_>>();
+            let shortest = *durations.iter().min().unwrap();
+            let longest = *durations.iter().max().unwrap();
+            assert!(shortest > 0);
+            (shortest, longest)
+        };
+
+        let (shortest, longest) = duration_range(None);
+        let (wide_shortest, wide_longest) = duration_range(Some(2.0));
+        assert!(
+            wide_longest - wide_shortest > longest - shortest,
+            "Widened durations ranged from {} to {}, normal durations from {} to {}",
+            wide_shortest,
+            wide_longest,
+            shortest,
+            longest
+        );
+
+        // widening past the average recovery time still leaves every infection a duration
+        let mut p = Virus.create_pathogen("Test", 0);
+        p.acquire_symptom(&VariableCourse(10.0).get_symptom(), None);
+        assert!(p.base_recovery_distance() < p.average_recovery_time());
+        duration_range(Some(10.0));
+    }
 }
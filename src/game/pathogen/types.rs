@@ -1,14 +1,12 @@
 use std::collections::hash_map::RandomState;
 use std::collections::HashSet;
 use std::rc::Rc;
-use std::sync::Arc;
 
-use structure::graph::Graph;
 use structure::time::{Time, TimeUnit};
 use structure::time::TimeUnit::Days;
 
 use crate::game::pathogen::Pathogen;
-use crate::game::pathogen::symptoms::{Symp, Symptom, SymptomMap, SymptomMapBuilder};
+use crate::game::pathogen::symptoms::{Symp, SymptomMap, SymptomMapBuilder};
 use crate::game::pathogen::symptoms::base::{Cough, RunnyNose};
 
 pub trait PathogenType {
@@ -19,7 +17,7 @@ pub trait PathogenType {
     fn get_mutativity(&self) -> f64;
     fn get_average_duration(&self) -> TimeUnit;
     fn get_duration_spread(&self) -> TimeUnit;
-    fn get_symptoms_map(&self) -> (Graph<usize, f64, Arc<Symptom>>, HashSet<usize>);
+    fn get_symptoms_map(&self) -> (SymptomMapBuilder, HashSet<usize>);
 
     fn create_pathogen(&self, name: &str, mutation_ticks: usize) -> Pathogen {
         let fixed_name = format!("{} {}", self.get_prefix(), name);
@@ -70,7 +68,7 @@ impl PathogenType for Virus {
         Days(3)
     }
 
-    fn get_symptoms_map(&self) -> (Graph<usize, f64, Arc<Symptom>>, HashSet<usize>) {
+    fn get_symptoms_map(&self) -> (SymptomMapBuilder, HashSet<usize>) {
         let mut builder = SymptomMapBuilder::new();
         let mut set = HashSet::new();
 
@@ -78,15 +76,17 @@ impl PathogenType for Virus {
         set.insert(builder_entry.node());
         builder_entry
             .next_symptom(Cough(1).get_symptom(), 0.5)
-            .next_symptom(Cough(2).get_symptom(), 0.02)
-            .next_symptom(Cough(3).get_symptom(), 0.01);
+            .upgrade_symptom(Cough(2).get_symptom(), 0.02)
+            .upgrade_symptom(Cough(3).get_symptom(), 0.01);
 
-        (builder.get_map(), set)
+        (builder, set)
     }
 }
 
 #[cfg(test)]
 mod test {
+    use std::sync::Arc;
+
     use structure::time::{Time, TimeUnit};
     use structure::time::TimeUnit::Minutes;
 
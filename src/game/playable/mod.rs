@@ -1 +1,61 @@
+use crate::game::pathogen::Pathogen;
 
+/// The amount of research points needed to understand a single symptom of a pathogen
+pub const RESEARCH_PER_SYMPTOM: usize = 100;
+
+///
+/// The player's effort to research a cure, which doctors use to treat the infected
+pub struct Player {
+    research_points: usize,
+}
+
+impl Player {
+    pub fn new() -> Self {
+        Self { research_points: 0 }
+    }
+
+    /// The total amount of research points that have been invested
+    pub fn research_points(&self) -> usize {
+        self.research_points
+    }
+
+    /// Puts more research points towards a cure
+    pub fn invest(&mut self, points: usize) {
+        self.research_points += points;
+    }
+
+    /// How close a cure for the pathogen is, in the range [0, 1]. Every acquired symptom has to
+    /// be understood, so pathogens with more symptoms take more research to cure.
+    pub fn cure_progress(&self, pathogen: &Pathogen) -> f64 {
+        let symptoms = usize::max(1, pathogen.get_acquired().len());
+        let understood = self.research_points as f64 / RESEARCH_PER_SYMPTOM as f64;
+        f64::min(1.0, understood / symptoms as f64)
+    }
+}
+
+impl Default for Player {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::game::pathogen::types::{PathogenType, Virus};
+    use crate::game::playable::{Player, RESEARCH_PER_SYMPTOM};
+
+    #[test]
+    fn investing_raises_cure_progress() {
+        let pathogen = Virus.default();
+        let mut player = Player::new();
+        assert_eq!(player.cure_progress(&pathogen), 0.0);
+
+        player.invest(RESEARCH_PER_SYMPTOM / 2);
+        let progress = player.cure_progress(&pathogen);
+        assert!(progress > 0.0 && progress < 1.0, "Progress was {}", progress);
+
+        player.invest(RESEARCH_PER_SYMPTOM * 10);
+        assert_eq!(player.cure_progress(&pathogen), 1.0);
+        assert_eq!(player.research_points(), RESEARCH_PER_SYMPTOM * 21 / 2);
+    }
+}
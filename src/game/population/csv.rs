@@ -0,0 +1,163 @@
+use std::fmt::{Display, Formatter};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, RwLock};
+
+use structure::time::Time;
+use structure::time::TimeUnit::{Days, Months, Years};
+
+use crate::game::Age;
+use crate::game::population::{Person, PersonBuilder, Population, Sex};
+
+/// The header row written by [Population::to_csv]
+pub const CSV_HEADER: &str = "age_years,age_months,age_days,sex,pre_existing_condition";
+
+#[derive(Debug)]
+pub enum CsvError {
+    Io(std::io::Error),
+    /// A row could not be parsed, `line` starts at 1
+    Parse { line: usize, message: String },
+}
+
+impl Display for CsvError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CsvError::Io(e) => write!(f, "{}", e),
+            CsvError::Parse { line, message } => write!(f, "Line {}: {}", line, message),
+        }
+    }
+}
+
+impl From<std::io::Error> for CsvError {
+    fn from(e: std::io::Error) -> Self {
+        CsvError::Io(e)
+    }
+}
+
+/// Splits an age into the years, months and days that [Age::new] would build it from. Any
+/// time left over that is less than a day is dropped.
+fn age_parts(age: &Age) -> (u16, usize, usize) {
+    let mut minutes = usize::from(age.time_unit().as_minutes());
+    let years = usize::from(age.time_unit().as_years()) as u16;
+    minutes -= usize::from(Years(years).into_minutes());
+
+    let mut months = 0;
+    while months < 11 && usize::from(Months(months + 1).into_minutes()) <= minutes {
+        months += 1;
+    }
+    minutes -= usize::from(Months(months).into_minutes());
+
+    let days = minutes / usize::from(Days(1).into_minutes());
+    (years, months, days)
+}
+
+fn parse_field<T: FromStr>(field: Option<&str>, name: &str, line: usize) -> Result<T, CsvError> {
+    let field = field.ok_or_else(|| CsvError::Parse {
+        line,
+        message: format!("Missing {}", name),
+    })?;
+    field.trim().parse::<T>().map_err(|_| CsvError::Parse {
+        line,
+        message: format!("Invalid {}: {}", name, field),
+    })
+}
+
+impl Population {
+    /// Creates a population from rows of `age_years, age_months, age_days, sex,
+    /// pre_existing_condition`, where sex is either `Male` or `Female`. Every person is created
+    /// through `builder`. A header row and blank lines are skipped.
+    pub fn from_csv<R: Read>(
+        builder: &Arc<Mutex<PersonBuilder>>,
+        reader: R,
+    ) -> Result<Population, CsvError> {
+        let mut people = Vec::new();
+
+        for (index, row) in BufReader::new(reader).lines().enumerate() {
+            let row = row?;
+            let line = index + 1;
+            if row.trim().is_empty() || (line == 1 && row.trim() == CSV_HEADER) {
+                continue;
+            }
+
+            let mut fields = row.split(',');
+            let years = parse_field::<u16>(fields.next(), "age_years", line)?;
+            let months = parse_field::<usize>(fields.next(), "age_months", line)?;
+            let days = parse_field::<usize>(fields.next(), "age_days", line)?;
+            let sex = match fields.next().map(|s| s.trim().to_lowercase()) {
+                Some(ref s) if s == "male" => Sex::Male,
+                Some(ref s) if s == "female" => Sex::Female,
+                other => {
+                    return Err(CsvError::Parse {
+                        line,
+                        message: format!("Invalid sex: {:?}", other),
+                    })
+                }
+            };
+            let pre_existing_condition =
+                parse_field::<f64>(fields.next(), "pre_existing_condition", line)?;
+
+            let person = builder.lock().unwrap().create_person(
+                Age::new(years, months, days),
+                sex,
+                pre_existing_condition,
+            );
+            people.push(Arc::new(RwLock::new(person)));
+        }
+
+        Ok(Population::from_people(builder, people))
+    }
+
+    /// Writes the demographics of every living person, in the format read by
+    /// [Population::from_csv]
+    pub fn to_csv<W: Write>(&self, mut writer: W) -> Result<(), CsvError> {
+        writeln!(writer, "{}", CSV_HEADER)?;
+        for person in &self.people {
+            let person: &Person = &person.read().unwrap();
+            let (years, months, days) = age_parts(&person.age.lock().unwrap());
+            writeln!(
+                writer,
+                "{},{},{},{:?},{}",
+                years, months, days, person.sex, person.pre_existing_condition
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::game::population::{PersonBuilder, Population, UniformDistribution};
+
+    #[test]
+    fn csv_round_trip() {
+        let pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            100,
+            UniformDistribution::new(0, 100),
+        );
+        let mut buffer = Vec::new();
+        pop.to_csv(&mut buffer).unwrap();
+
+        let imported = Population::from_csv(&PersonBuilder::new(), buffer.as_slice()).unwrap();
+        assert_eq!(imported.get_everyone().len(), pop.get_everyone().len());
+        for (original, copy) in pop.get_everyone().iter().zip(imported.get_everyone()) {
+            let original = original.read().unwrap();
+            let copy = copy.read().unwrap();
+            assert_eq!(*original.age.lock().unwrap(), *copy.age.lock().unwrap());
+            assert_eq!(original.sex, copy.sex);
+            assert_eq!(original.pre_existing_condition, copy.pre_existing_condition);
+        }
+    }
+
+    #[test]
+    fn csv_reports_bad_rows() {
+        let data = "age_years,age_months,age_days,sex,pre_existing_condition\n\
+                    30,1,2,Female,0.5\n\
+                    40,1,2,Robot,0.5\n";
+        match Population::from_csv(&PersonBuilder::new(), data.as_bytes()) {
+            Err(super::CsvError::Parse { line, .. }) => assert_eq!(line, 3),
+            _ => panic!("Should not have parsed an invalid sex"),
+        }
+    }
+}
@@ -1,35 +1,246 @@
 use std::borrow::{Borrow, BorrowMut};
 use std::cell::{Ref, RefCell};
 use std::cmp::{min, Ordering};
+use std::collections::HashMap;
 use std::fmt::{Debug, Display, Error, Formatter, Result};
 use std::mem;
-use std::ops::DerefMut;
+use std::ops::{DerefMut, Range};
 use std::rc::Rc;
-use std::sync::{Arc, Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard};
+use std::sync::{
+    Arc, Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard,
+    TryLockError,
+};
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering::Relaxed;
 
-use rand::{random, Rng};
+use rand::rngs::StdRng;
+use rand::{random, Rng, RngCore, SeedableRng};
+use rayon::prelude::*;
 
-use structure::time::Time;
+use structure::time::{Time, TimeUnit};
+use structure::time::TimeUnit::{Minutes, Years};
 
-use crate::game::{Age, ParallelUpdate, roll, tick_to_game_time_conversion, Update};
-use crate::game::pathogen::infection::Infection;
+use crate::game::{Age, ParallelUpdate, roll_with, TICKS_TO_GAME_MIN, Update};
+use crate::game::pathogen::infection::{Infection, DEFAULT_INITIAL_DOSE};
 use crate::game::pathogen::Pathogen;
 use crate::game::pathogen::symptoms::Symp;
 use crate::game::population::Condition::Normal;
+use crate::game::population::person_behavior::interaction::DEFAULT_CONTACT_RATE;
+use crate::game::population::person_behavior::mixing::InteractionMatrix;
+use crate::game::population::person_behavior::transmission::TransmissionTree;
 use crate::game::population::Sex::{Female, Male};
 
+pub mod csv;
 pub mod person_behavior;
 
-#[derive(Debug, Eq, PartialEq)]
+/// The default amount of game time between fatality rolls of an active infection
+pub const DEFAULT_DAMAGE_INTERVAL: TimeUnit = Minutes(1);
+/// The default fraction of a person's max health that can be lost in a single tick
+pub const DEFAULT_MAX_DAMAGE_FRACTION: f64 = 0.05;
+/// The oldest age, in years, that the health of a person keeps declining until
+pub const MAX_AGE: u8 = 120;
+/// The default chance that someone who needs a hospital but can't get a bed dies during triage
+pub const DEFAULT_TRIAGE_FATALITY: f64 = 0.01;
+/// The default immunity left behind by the mildest infection, which makes recovery permanent
+pub const DEFAULT_BASE_IMMUNITY: f64 = 1.0;
+/// The default fraction of immunity that carries over to a completely different strain of the
+/// same pathogen, which makes immunity ignore how a pathogen has mutated
+pub const DEFAULT_CROSS_IMMUNITY: f64 = 1.0;
+
+/// Names a group of people that are analyzed separately, such as those who were vaccinated
+pub type CohortId = String;
+
+/// Decides how a person's infections can kill them
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MortalityModel {
+    /// Active infections roll to damage their host every damage interval
+    PerTick,
+    /// Whether an infection is fatal is decided once when it's caught, from a base case
+    /// fatality rate adjusted for the age of the host and the severity of the pathogen. Health
+    /// only declines for those who will die, who die once the infection runs its course.
+    CaseFatality(f64),
+}
+
+impl MortalityModel {
+    /// How much more likely a person of this age is to die than an adult
+    fn age_factor(age: u8) -> f64 {
+        match age {
+            0..=19 => 0.5,
+            20..=59 => 1.0,
+            _ => 2.0,
+        }
+    }
+
+    /// The chance that an infection caught by a person of `age` is fatal
+    pub fn fatality_chance(&self, age: u8, severity: f64) -> f64 {
+        match self {
+            MortalityModel::PerTick => 0.0,
+            MortalityModel::CaseFatality(rate) => {
+                f64::min(1.0, rate * Self::age_factor(age) * (1.0 + severity))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum Condition {
     Normal,
     NeedsHospital,
     Hospitalized,
 }
 
-#[derive(Debug)]
+/// The SEIR (plus deaths) compartment that a person is in
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Compartment {
+    Susceptible,
+    /// Infected, but the infection hasn't become active enough to spread yet
+    Exposed,
+    Infectious,
+    Recovered,
+    Dead,
+}
+
+/// The amount of people in each compartment of a population
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct CompartmentCounts {
+    pub susceptible: usize,
+    pub exposed: usize,
+    pub infectious: usize,
+    pub recovered: usize,
+    pub dead: usize,
+}
+
+impl CompartmentCounts {
+    pub fn get(&self, compartment: Compartment) -> usize {
+        match compartment {
+            Compartment::Susceptible => self.susceptible,
+            Compartment::Exposed => self.exposed,
+            Compartment::Infectious => self.infectious,
+            Compartment::Recovered => self.recovered,
+            Compartment::Dead => self.dead,
+        }
+    }
+
+    fn add(&mut self, compartment: Compartment) {
+        match compartment {
+            Compartment::Susceptible => self.susceptible += 1,
+            Compartment::Exposed => self.exposed += 1,
+            Compartment::Infectious => self.infectious += 1,
+            Compartment::Recovered => self.recovered += 1,
+            Compartment::Dead => self.dead += 1,
+        }
+    }
+}
+
+/// What happened when an infected person interacted with someone else
+#[derive(Debug, Clone)]
+pub enum InteractionOutcome {
+    /// The infector had nothing that could spread to the other person, either because none of
+    /// their infections were transmissible or because the other person was immune to all of them
+    NotInfectious,
+    /// At least one infection had a chance to spread, but none did
+    NoTransmission,
+    /// The other person was infected, with `strain` being the first strain that took hold
+    Infected { strain: Arc<Pathogen> },
+}
+
+impl InteractionOutcome {
+    /// Whether the other person became infected
+    pub fn infected(&self) -> bool {
+        matches!(self, InteractionOutcome::Infected { .. })
+    }
+}
+
+/// Sent when a person's health drops below one of the fractions of max health being watched
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct HealthEvent {
+    /// The id of the person whose health dropped
+    pub person: usize,
+    /// The fraction of max health that was crossed
+    pub threshold: f64,
+    /// The person's health points after the drop
+    pub health_points: u32,
+}
+
+///
+/// Watches people's health, calling an observer whenever someone's health drops below one of a
+/// set of fractions of their max health. Every crossing is only reported once, on the update in
+/// which it happened, so frontends don't have to poll everyone every frame.
+pub struct HealthWatch {
+    thresholds: Vec<f64>,
+    observer: Box<dyn Fn(HealthEvent) + Send + Sync>,
+}
+
+impl HealthWatch {
+    /// Creates a watch that calls `observer` when health drops below any of the `thresholds`,
+    /// which are fractions of max health
+    ///
+    /// # Panics
+    /// Panics if a threshold is not within the range (0, 1]
+    pub fn new(thresholds: &[f64], observer: Box<dyn Fn(HealthEvent) + Send + Sync>) -> Self {
+        for &threshold in thresholds {
+            if !(threshold > 0.0 && threshold <= 1.0) {
+                panic!("Invalid health threshold: {}", threshold);
+            }
+        }
+        Self {
+            thresholds: thresholds.to_vec(),
+            observer,
+        }
+    }
+
+    /// The fractions of max health being watched
+    pub fn thresholds(&self) -> &[f64] {
+        &self.thresholds
+    }
+
+    /// Notifies the observer of every threshold crossed while the health fraction of `person`
+    /// went from `before` to `after`
+    fn check(&self, person: usize, before: f64, after: f64, health_points: u32) {
+        for &threshold in &self.thresholds {
+            if before >= threshold && after < threshold {
+                (self.observer)(HealthEvent {
+                    person,
+                    threshold,
+                    health_points,
+                });
+            }
+        }
+    }
+}
+
+/// A summary of a population, gathered in a single pass over everyone, including the deceased
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct PopulationStats {
+    pub susceptible: usize,
+    pub exposed: usize,
+    pub infectious: usize,
+    pub recovered: usize,
+    pub dead: usize,
+    /// Everyone who has had an infection at some point, whether they're still alive or not
+    pub ever_infected: usize,
+    /// The size the population started at
+    pub original: usize,
+}
+
+impl PopulationStats {
+    /// The amount of active cases, both exposed and infectious
+    pub fn infected(&self) -> usize {
+        self.exposed + self.infectious
+    }
+
+    /// The amount of people still alive
+    pub fn living(&self) -> usize {
+        self.susceptible + self.infected() + self.recovered
+    }
+
+    /// The amount of people counted, living or dead
+    pub fn total(&self) -> usize {
+        self.living() + self.dead
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum Sex {
     Male,
     Female,
@@ -37,6 +248,9 @@ pub enum Sex {
 
 trait HealthModifier {
     fn get_health_modification_factor(&self) -> f64;
+
+    /// Copies the modifier, so that copies of a person keep it
+    fn box_clone(&self) -> Box<dyn HealthModifier + Sync + Send>;
 }
 
 impl HealthModifier for Sex {
@@ -46,6 +260,59 @@ impl HealthModifier for Sex {
             Sex::Female => 1.0,
         }
     }
+
+    fn box_clone(&self) -> Box<dyn HealthModifier + Sync + Send> {
+        Box::new(*self)
+    }
+}
+
+/// A person's health points, which can never go below 0
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct HealthPoints(u32);
+
+impl HealthPoints {
+    pub fn new(points: u32) -> Self {
+        Self(points)
+    }
+
+    pub fn get(&self) -> u32 {
+        self.0
+    }
+
+    /// Takes away `amount` health points, stopping at 0
+    ///
+    /// ###Return
+    /// The amount of health points that were actually lost
+    pub fn saturating_damage(&mut self, amount: u32) -> u32 {
+        let lost = u32::min(self.0, amount);
+        self.0 -= lost;
+        lost
+    }
+
+    /// Restores `amount` health points, stopping at `max`. Health that is already above `max` is
+    /// left alone.
+    pub fn heal(&mut self, amount: u32, max: u32) {
+        if self.0 < max {
+            self.0 = u32::min(max, self.0.saturating_add(amount));
+        }
+    }
+
+    /// Lowers the health points to `max` if they're above it
+    pub fn cap(&mut self, max: u32) {
+        self.0 = u32::min(self.0, max);
+    }
+}
+
+impl PartialEq<u32> for HealthPoints {
+    fn eq(&self, other: &u32) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialOrd<u32> for HealthPoints {
+    fn partial_cmp(&self, other: &u32) -> Option<Ordering> {
+        self.0.partial_cmp(other)
+    }
 }
 
 ///
@@ -56,11 +323,23 @@ pub struct Person {
     age: Mutex<Age>,
     sex: Sex,
     pre_existing_condition: f64,
-    health_points: RwLock<u32>,
+    health_points: RwLock<HealthPoints>,
+    total_health_lost: u64, // every health point taken by updates since the last reset
     condition: Mutex<Condition>,
     modifiers: Mutex<Vec<Box<dyn HealthModifier + Sync + Send>>>,
-    infection: Mutex<Option<Infection>>,
-    recovered_status: RwLock<bool>,
+    infections: Mutex<HashMap<String, Infection>>, // infections keyed by the name of their pathogen
+    vaccinations: HashMap<String, Arc<Pathogen>>, // the strains vaccinated against, keyed by name
+    damage_interval: TimeUnit,
+    ticks_since_damage_roll: usize,
+    ticks_since_aging: usize, // ticks too short to make up a whole game minute of age yet
+    max_damage_fraction: f64,
+    birthday: Option<u8>,
+    mortality_model: MortalityModel,
+    base_immunity: f64,
+    cross_immunity: f64,
+    quarantined: bool,
+    cohort: Option<CohortId>,
+    health_watch: Option<Arc<HealthWatch>>,
 }
 
 impl Display for Person {
@@ -97,34 +376,94 @@ impl Person {
             age: Mutex::new(age),
             sex,
             pre_existing_condition,
-            health_points: RwLock::new(health),
+            health_points: RwLock::new(HealthPoints::new(health)),
+            total_health_lost: 0,
             condition: Mutex::new(Normal),
             modifiers: Mutex::new(Vec::new()),
-            infection: Mutex::new(None),
-            recovered_status: RwLock::new(false),
+            infections: Mutex::new(HashMap::new()),
+            vaccinations: HashMap::new(),
+            damage_interval: DEFAULT_DAMAGE_INTERVAL,
+            ticks_since_damage_roll: 0,
+            ticks_since_aging: 0,
+            max_damage_fraction: DEFAULT_MAX_DAMAGE_FRACTION,
+            birthday: None,
+            mortality_model: MortalityModel::PerTick,
+            base_immunity: DEFAULT_BASE_IMMUNITY,
+            cross_immunity: DEFAULT_CROSS_IMMUNITY,
+            quarantined: false,
+            cohort: None,
+            health_watch: None,
         }
     }
 
-    /// Determines the maximum health for a person depending on a few conditions
+    /// Determines the maximum health for a person depending on a few conditions. Health stops
+    /// declining just before [MAX_AGE], so the very old still have some health.
     fn max_health(age: u8, sex: &Sex, pre_existing_condition: f64) -> u32 {
         ((match age {
             0..=3 => 30.0,
             4..=9 => 70.0,
             10..=19 => 100.0,
-            age => 10.0 * (-(age as i16) as f64 + 120.0).sqrt(),
+            age => 10.0 * f64::max(1.0, MAX_AGE.saturating_sub(age) as f64).sqrt(),
         }) * 10.0
             * sex.get_health_modification_factor()
             * pre_existing_condition) as u32
     }
 
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
     pub fn condition(&self) -> f64 {
-        (*self.health_points.read().unwrap() as f64 / 1000.0) * self.pre_existing_condition
+        (self.health_points.read().unwrap().get() as f64 / 1000.0) * self.pre_existing_condition
+    }
+
+    /// Whether the person is doing fine, needs a hospital, or is in one
+    pub fn condition_state(&self) -> Condition {
+        *self.condition.lock().unwrap()
     }
 
-    pub fn health_points(&self) -> &RwLock<u32> {
+    pub fn health_points(&self) -> &RwLock<HealthPoints> {
         &self.health_points
     }
 
+    /// Every health point the person has lost during their updates since they were created or
+    /// last reset, so that how hard each person's illness hit them can be compared after a run
+    pub fn total_health_lost(&self) -> u64 {
+        self.total_health_lost
+    }
+
+    /// The most health points the person can have at their current age
+    pub fn max_health_points(&self) -> u32 {
+        Self::max_health(self.get_age_years(), &self.sex, self.pre_existing_condition)
+    }
+
+    /// Permanently lowers the person's pre-existing condition factor by the fraction `damage`,
+    /// which lowers their max health for the rest of their life
+    pub fn suffer_lasting_damage(&mut self, damage: f64) {
+        self.pre_existing_condition *= 1.0 - damage;
+        let max_health = self.max_health_points();
+        self.health_points.write().unwrap().cap(max_health);
+    }
+
+    /// Restores `amount` of the person's health points, without going over the max for their
+    /// age. The dead can't be healed.
+    pub fn heal(&mut self, amount: u32) {
+        if self.alive() {
+            let max_health = self.max_health_points();
+            self.health_points.write().unwrap().heal(amount, max_health);
+        }
+    }
+
+    /// The person's health relative to their max health, in the range [0, 1]
+    pub fn health_fraction(&self) -> f64 {
+        let max_health = self.max_health_points();
+        if max_health == 0 {
+            return 0.0;
+        }
+        let health = self.health_points.read().unwrap().get();
+        f64::min(1.0, health as f64 / max_health as f64)
+    }
+
     pub fn alive(&self) -> bool {
         *self.health_points.read().unwrap() > 0
     }
@@ -134,211 +473,853 @@ impl Person {
     }
 
     pub fn never_infected(&self) -> bool {
-        self.infection.lock().unwrap().is_none()
+        self.infections.lock().unwrap().is_empty()
     }
 
+    /// Whether the person is currently fighting off at least one infection
     pub fn infected(&self) -> bool {
         if self.dead() {
             return false;
         }
-        match &*self.infection.lock().unwrap() {
-            None => false,
-            Some(i) => !i.recovered(),
-        }
+        self.infections
+            .lock()
+            .unwrap()
+            .values()
+            .any(|i| !i.recovered())
     }
 
+    /// Whether the person has recovered from every infection they've had
     pub fn recovered(&self) -> bool {
         if self.dead() {
             return false;
         }
-        *self.recovered_status.read().unwrap()
+        let infections = self.infections.lock().unwrap();
+        !infections.is_empty() && infections.values().all(|i| i.recovered())
     }
 
-    /// Removes the immunity from someone
-    pub fn remove_immunity(&mut self) {
-        if self.recovered() && self.infection.lock().unwrap().is_some() {
-            *self.infection.lock().unwrap() = None;
-            *self.recovered_status.write().unwrap() = false;
+    /// Whether the person has recovered from an infection but is still shedding it
+    pub fn shedding(&self) -> bool {
+        if self.dead() {
+            return false;
         }
+        self.infections
+            .lock()
+            .unwrap()
+            .values()
+            .any(|i| i.shedding())
     }
 
-    pub fn infect(&mut self, pathogen: &Arc<Pathogen>) -> bool {
-        if self.infection.lock().unwrap().is_none() {
-            *self.infection.lock().unwrap() =
-                Some(Infection::new(pathogen.clone(), self.condition()));
-            true
-        } else {
-            false
+    /// Whether the person has an infection that is active enough to spread
+    pub fn infectious(&self) -> bool {
+        if self.dead() {
+            return false;
         }
+        self.infections
+            .lock()
+            .unwrap()
+            .values()
+            .any(|i| i.active_case())
     }
 
-    /// Perform an interaction with another person
-    ///
-    /// ###Return
-    /// Whether the other person just became infected
-    pub fn interact_with(&self, other: &mut Person) -> bool {
-        if other.infected() || other.recovered() {
-            return false;
+    pub fn compartment(&self) -> Compartment {
+        if self.dead() {
+            Compartment::Dead
+        } else if self.infectious() {
+            Compartment::Infectious
+        } else if self.infected() {
+            Compartment::Exposed
+        } else if self.recovered() {
+            Compartment::Recovered
+        } else {
+            Compartment::Susceptible
         }
-        if self.infected() {
-            if let Some(ref infection) = *self.infection.lock().unwrap() {
-                if infection.active_case() {
-                    if roll(infection.get_pathogen().catch_chance()) {
-                        let pathogen = Arc::new(infection.get_pathogen().mutate());
+    }
 
-                        return other.infect(&pathogen);
-                    }
-                }
-            }
+    /// Whether the person has ever been infected by a pathogen with this name
+    pub fn infected_with(&self, pathogen_name: &str) -> bool {
+        self.infections.lock().unwrap().contains_key(pathogen_name)
+    }
+
+    /// Gets a copy of every infection the person has had
+    pub fn infections(&self) -> Vec<Infection> {
+        self.infections.lock().unwrap().values().cloned().collect()
+    }
+
+    /// The highest severity among the person's current infections
+    pub fn severity(&self) -> f64 {
+        self.infections
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|i| !i.recovered())
+            .map(|i| i.get_pathogen().severity())
+            .fold(0.0, f64::max)
+    }
+
+    /// Whether any of the person's infections are severe enough for them to need hospital care
+    fn seeks_hospital_care(&self) -> bool {
+        self.infections
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|i| !i.recovered())
+            .any(|i| i.get_pathogen().severity() > i.get_pathogen().hospitalization_threshold())
+    }
+
+    /// How willing the person is to go about their normal life, such as interacting with others
+    /// or traveling, in the range [0, 1]. Severe infections and needing hospital care suppress it.
+    pub fn behavioral_activity(&self) -> f64 {
+        if self.dead() || self.quarantined {
+            return 0.0;
         }
-        false
+        let condition_effect = match *self.condition.lock().unwrap() {
+            Condition::Normal => 1.0,
+            Condition::NeedsHospital => 0.5,
+            Condition::Hospitalized => 0.05,
+        };
+        (1.0 - self.severity()) * condition_effect
     }
 
-    fn get_age_years(&self) -> u8 {
-        usize::from(self.age.lock().unwrap().0.as_years()) as u8
+    /// The chance that the person is detected as infected, from the most noticeable of their
+    /// active infections. Both the severity and the symptoms of an infection make it noticeable.
+    pub fn detection_chance(&self) -> f64 {
+        self.infections
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|i| i.active_case())
+            .map(|i| {
+                let pathogen = i.get_pathogen();
+                f64::min(1.0, pathogen.severity() + pathogen.detectability())
+            })
+            .fold(0.0, f64::max)
     }
-}
 
-impl PartialEq for Person {
-    fn eq(&self, other: &Self) -> bool {
-        self.id == other.id
+    /// Whether the person is in quarantine, where they make no contacts
+    pub fn quarantined(&self) -> bool {
+        self.quarantined
     }
-}
 
-impl Update for Person {
-    fn update_self(&mut self, delta_time: usize) {
-        {
-            match &mut *self.infection.lock().unwrap() {
-                // update infection
-                None => {}
-                Some(i) => {
-                    i.update(delta_time);
-                }
-            }
+    pub fn set_quarantined(&mut self, quarantined: bool) {
+        self.quarantined = quarantined;
+    }
+
+    /// The cohort the person was assigned to, if any
+    pub fn cohort(&self) -> Option<&str> {
+        self.cohort.as_deref()
+    }
+
+    pub fn set_cohort(&mut self, cohort: Option<CohortId>) {
+        self.cohort = cohort;
+    }
+
+    /// Removes the immunity from someone, including the immunity from their vaccinations
+    pub fn remove_immunity(&mut self) {
+        self.infections
+            .lock()
+            .unwrap()
+            .retain(|_, infection| !infection.recovered());
+        self.vaccinations.clear();
+    }
+
+    /// Infects the person with a pathogen, which fails if they've already been infected by a
+    /// pathogen with the same name, or if the infection doesn't take hold
+    pub fn infect(&mut self, pathogen: &Arc<Pathogen>) -> bool {
+        self.infect_with_dose(pathogen, DEFAULT_INITIAL_DOSE)
+    }
+
+    /// How strongly the person resists being infected by a pathogen with this name, in the range
+    /// [0, 1]. Someone who recovered or was vaccinated is left with at least the base immunity,
+    /// and more severe strains leave behind stronger immunity.
+    pub fn immunity(&self, pathogen_name: &str) -> f64 {
+        let from_infection = match self.infections.lock().unwrap().get(pathogen_name) {
+            None => 0.0,
+            Some(infection) if !infection.recovered() => 1.0,
+            Some(infection) => self.immunity_from(infection.get_pathogen()),
+        };
+        let from_vaccine = self
+            .vaccinations
+            .get(pathogen_name)
+            .map_or(0.0, |vaccine| self.immunity_from(vaccine));
+        f64::max(from_infection, from_vaccine)
+    }
+
+    /// How strongly the person resists being infected by this strain, in the range [0, 1]. The
+    /// immunity left behind by recovering from or being vaccinated against a pathogen with the
+    /// same name only fully applies to that strain, and weakens down to the cross immunity the
+    /// less similar the strain is.
+    pub fn immunity_to(&self, strain: &Pathogen) -> f64 {
+        let from_infection = match self.infections.lock().unwrap().get(strain.name()) {
+            None => 0.0,
+            Some(infection) if !infection.recovered() => 1.0,
+            Some(infection) => self.strain_immunity_from(infection.get_pathogen(), strain),
+        };
+        let from_vaccine = self
+            .vaccinations
+            .get(strain.name())
+            .map_or(0.0, |vaccine| self.strain_immunity_from(vaccine, strain));
+        f64::max(from_infection, from_vaccine)
+    }
+
+    /// The immunity left behind by recovering from or being vaccinated against `source`
+    fn immunity_from(&self, source: &Pathogen) -> f64 {
+        self.base_immunity + (1.0 - self.base_immunity) * source.severity()
+    }
+
+    /// The immunity that `source` leaves behind against `strain`
+    fn strain_immunity_from(&self, source: &Pathogen, strain: &Pathogen) -> f64 {
+        let similarity = source.similarity(strain);
+        let cross = self.cross_immunity + (1.0 - self.cross_immunity) * similarity;
+        self.immunity_from(source) * cross
+    }
+
+    /// Infects the person with a pathogen, starting the infection with `dose` pathogens. Someone
+    /// who recovered from a pathogen with the same name can only be reinfected if it gets past
+    /// their immunity to the strain.
+    pub fn infect_with_dose(&mut self, pathogen: &Arc<Pathogen>, dose: usize) -> bool {
+        self.infect_with_rng(pathogen, dose, &mut rand::thread_rng())
+    }
+
+    /// Infects the person with a dose of a pathogen, using `rng` for every roll
+    fn infect_with_rng<R: Rng>(
+        &mut self,
+        pathogen: &Arc<Pathogen>,
+        dose: usize,
+        rng: &mut R,
+    ) -> bool {
+        if self.dead() || rng.gen_bool(self.immunity_to(pathogen)) {
+            return false;
+        }
+        if !rng.gen_bool(pathogen.establishment_chance()) {
+            return false;
         }
+        let mut infection =
+            Infection::with_dose_rng(pathogen.clone(), self.condition(), dose, rng);
+        let fatality = self
+            .mortality_model
+            .fatality_chance(self.get_age_years(), pathogen.severity());
+        infection.set_fatal(rng.gen_bool(fatality));
+        self.infections
+            .lock()
+            .unwrap()
+            .insert(pathogen.name().clone(), infection);
+        true
+    }
 
+    /// Vaccinates the person against a pathogen, giving them the immunity of having recovered
+    /// from it without counting them as ever infected. Fails if they're dead, have already been
+    /// infected by a pathogen with the same name, or were already vaccinated against it.
+    pub fn vaccinate(&mut self, pathogen: &Arc<Pathogen>) -> bool {
+        if self.dead()
+            || self.infected_with(pathogen.name())
+            || self.vaccinated_against(pathogen.name())
         {
-            // update age
-            let mut age_guard = self.age.lock().unwrap();
-            *age_guard += tick_to_game_time_conversion(delta_time);
+            return false;
         }
+        self.vaccinations.insert(pathogen.name().clone(), pathogen.clone());
+        true
+    }
 
-        if !self.recovered() {
-            // update recover status
-            let infection_recovered = {
-                let guard1 = &*self.infection.lock().unwrap();
-                if let Some(i) = guard1 {
-                    i.recovered()
-                } else {
-                    false
-                }
-            };
+    /// Whether the person was vaccinated against a pathogen with this name
+    pub fn vaccinated_against(&self, pathogen_name: &str) -> bool {
+        self.vaccinations.contains_key(pathogen_name)
+    }
 
-            if infection_recovered {
-                *self.recovered_status.write().unwrap() = true;
-                *self.condition.lock().unwrap() = Normal;
-                let mut lock = self.infection.lock();
-                let guard = (&*lock.unwrap()).clone();
-                {
-                    match guard {
-                        None => {}
-                        Some(i) => {
-                            i.get_pathogen().perform_recovery(self);
-                        }
-                    }
+    /// Cures the person's infection by a pathogen with this name, performing its recovery effects
+    ///
+    /// ###Return
+    /// Whether there was an active infection to cure
+    pub fn cure(&mut self, pathogen_name: &str) -> bool {
+        let pathogen = {
+            let mut infections = self.infections.lock().unwrap();
+            match infections.get_mut(pathogen_name) {
+                Some(infection) if !infection.recovered() => {
+                    infection.cure();
+                    infection.get_pathogen().clone()
                 }
+                _ => return false,
             }
+        };
+        if !self.infected() {
+            *self.condition.lock().unwrap() = Normal;
         }
+        pathogen.perform_recovery(self);
+        true
+    }
 
-        // update health points and condition
-        {
-            let max_health = {
-                let mut hp_guard = self.health_points.write().unwrap();
-                let max_health =
-                    Self::max_health(self.get_age_years(), &self.sex, self.pre_existing_condition);
+    /// How long, in minutes, the person's body stays infectious after their death, which is the
+    /// longest post-mortem infectious period of the pathogens they were infected with
+    pub fn post_mortem_infectious_period(&self) -> usize {
+        self.infections
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|i| i.post_mortem_transmissible())
+            .map(|i| i.get_pathogen().post_mortem_infectious_period())
+            .max()
+            .unwrap_or(0)
+    }
 
-                if max_health < *hp_guard {
-                    *hp_guard = max_health;
-                }
+    /// Perform an interaction with another person
+    ///
+    /// ###Return
+    /// Whether the other person just became infected
+    pub fn interact_with(&self, other: &mut Person) -> bool {
+        self.interact_with_recorded(other, None)
+    }
 
-                max_health
-            };
+    /// Perform an interaction with another person, logging the transmission to `recorder` if the
+    /// other person became infected. Each active infection gets its own chance to spread.
+    ///
+    /// ###Return
+    /// Whether the other person just became infected
+    pub fn interact_with_recorded(
+        &self,
+        other: &mut Person,
+        recorder: Option<&TransmissionTree>,
+    ) -> bool {
+        self.interact_with_rng(other, recorder, &mut rand::thread_rng())
+    }
 
-            if self.infected() {
-                let mut rate = 1.0;
-                let get_hurt = {
-                    // remove infection mutex as fast as possible
-                    match &*self.infection.lock().unwrap() {
-                        None => panic!("Infection must exist"),
-                        Some(i) => {
-                            if !i.active_case() {
-                                false
-                            } else {
-                                rate = 1.0 / (1.0 - i.get_pathogen().severity());
-                                roll(i.get_pathogen().fatality())
-                            }
-                        }
-                    }
-                };
+    /// Perform an interaction with another person, using `rng` for every roll so that a seeded rng
+    /// gives a reproducible interaction. Infections are tried in the order of their names.
+    ///
+    /// ###Return
+    /// Whether the other person just became infected
+    pub fn interact_with_rng<R: Rng>(
+        &self,
+        other: &mut Person,
+        recorder: Option<&TransmissionTree>,
+        rng: &mut R,
+    ) -> bool {
+        self.interaction_outcome(other, recorder, rng).infected()
+    }
 
-                if get_hurt {
-                    let change = &mut *self.condition.lock().unwrap();
-                    let mut hp_guard = self.health_points.write().unwrap();
-                    *hp_guard -= u32::min(
-                        *hp_guard,
-                        ((match change {
-                            Condition::Normal => 1.0,
-                            Condition::NeedsHospital => 3.0,
-                            Condition::Hospitalized => 2.0,
-                        }) * rate) as u32,
-                    );
+    /// Perform an interaction with another person like [Person::interact_with_rng], telling apart
+    /// whether the infector could spread anything at all, and which strain was transmitted
+    pub fn interaction_outcome<R: Rng>(
+        &self,
+        other: &mut Person,
+        recorder: Option<&TransmissionTree>,
+        rng: &mut R,
+    ) -> InteractionOutcome {
+        self.interaction_outcome_at(other, recorder, None, rng)
+    }
 
-                    if *change == Condition::Normal {
-                        match *hp_guard {
-                            hp if hp < max_health / 4 => {
-                                *change = Condition::NeedsHospital;
-                            }
-                            _ => {}
-                        }
+    /// Perform an interaction with another person like [Person::interaction_outcome], with the
+    /// catch chance of seasonal pathogens scaled for `time_of_year`. Without a time of year every
+    /// pathogen spreads at its average catch chance.
+    pub fn interaction_outcome_at<R: Rng>(
+        &self,
+        other: &mut Person,
+        recorder: Option<&TransmissionTree>,
+        time_of_year: Option<&TimeUnit>,
+        rng: &mut R,
+    ) -> InteractionOutcome {
+        // nobody can interact with themselves
+        if self.id == other.id {
+            return InteractionOutcome::NotInfectious;
+        }
+        // the dead can only spread what their body stays infectious with
+        let body = self.dead();
+        let mut output = InteractionOutcome::NotInfectious;
+        let infections = self.infections.lock().unwrap();
+        let mut names = infections.keys().collect::<Vec<_>>();
+        names.sort();
+        for infection in names.into_iter().map(|name| &infections[name]) {
+            let transmissible = if body {
+                infection.post_mortem_transmissible()
+            } else {
+                infection.transmissible()
+            };
+            if !transmissible || other.immunity_to(infection.get_pathogen()) >= 1.0 {
+                continue;
+            }
+            if !output.infected() {
+                output = InteractionOutcome::NoTransmission;
+            }
+            let catch_chance = match time_of_year {
+                Some(time_of_year) => infection.catch_chance_at(time_of_year),
+                None => infection.catch_chance(),
+            };
+            if rng.gen_bool(catch_chance) {
+                let pathogen = Arc::new(infection.get_pathogen().select_mutation_with(rng));
+
+                if other.infect_with_rng(&pathogen, infection.transmitted_dose(), rng) {
+                    if let Some(recorder) = recorder {
+                        recorder.record(self.id, other.id);
+                    }
+                    if !output.infected() {
+                        output = InteractionOutcome::Infected { strain: pathogen };
                     }
                 }
             }
         }
+        output
     }
-}
 
-pub struct PersonBuilder {
-    count: usize,
-}
+    /// Creates an independent person with a new id that is in the same state as this one
+    fn deep_copy(&self, id: usize) -> Person {
+        Person {
+            id,
+            age: Mutex::new(self.age.lock().unwrap().clone()),
+            sex: self.sex,
+            pre_existing_condition: self.pre_existing_condition,
+            health_points: RwLock::new(*self.health_points.read().unwrap()),
+            total_health_lost: self.total_health_lost,
+            condition: Mutex::new(self.condition_state()),
+            modifiers: Mutex::new(
+                self.modifiers
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .map(|m| m.box_clone())
+                    .collect(),
+            ),
+            infections: Mutex::new(self.infections.lock().unwrap().clone()),
+            vaccinations: self.vaccinations.clone(),
+            damage_interval: self.damage_interval.clone(),
+            ticks_since_damage_roll: self.ticks_since_damage_roll,
+            ticks_since_aging: self.ticks_since_aging,
+            max_damage_fraction: self.max_damage_fraction,
+            birthday: self.birthday,
+            mortality_model: self.mortality_model,
+            base_immunity: self.base_immunity,
+            cross_immunity: self.cross_immunity,
+            quarantined: self.quarantined,
+            cohort: self.cohort.clone(),
+            health_watch: self.health_watch.clone(),
+        }
+    }
 
-impl PersonBuilder {
-    pub fn new() -> Arc<Mutex<PersonBuilder>> {
-        Arc::new(Mutex::new(Self { count: 0 }))
+    /// Clears every infection and vaccination and restores the person to full health
+    pub fn reset(&mut self) {
+        self.infections.lock().unwrap().clear();
+        self.vaccinations.clear();
+        *self.condition.lock().unwrap() = Normal;
+        *self.health_points.write().unwrap() = HealthPoints::new(Self::max_health(
+            self.get_age_years(),
+            &self.sex,
+            self.pre_existing_condition,
+        ));
+        self.total_health_lost = 0;
+        self.ticks_since_damage_roll = 0;
+        self.birthday = None;
+        self.quarantined = false;
     }
 
-    fn create_person(&mut self, age: Age, sex: Sex, pre_existing_condition: f64) -> Person {
-        let id = self.count;
-        self.count += 1;
-        Person::new(id, age, sex, pre_existing_condition)
+    /// Sets the age of the person, lowering their health if it's above the maximum for the new age
+    pub fn set_age(&mut self, age: Age) {
+        *self.age.lock().unwrap() = age;
+        let max_health =
+            Self::max_health(self.get_age_years(), &self.sex, self.pre_existing_condition);
+        self.health_points.write().unwrap().cap(max_health);
     }
-}
 
-pub struct Population {
-    factory: Arc<Mutex<PersonBuilder>>,
-    people: Vec<Arc<RwLock<Person>>>,
-    original_pop: usize,
-    current_pop: usize,
-    infected: Vec<Arc<RwLock<Person>>>,
-    growth_rate: f64,
-}
+    /// If the person entered a new year of age during the last update, gets the age in years
+    /// they turned. Populations report every birthday through [Population::on_birthday].
+    pub fn birthday(&self) -> Option<u8> {
+        self.birthday
+    }
 
-/// Represents the distribution of ages in a population
-pub trait PopulationDistribution {
-    /// Gets the percent of the population of an age
-    /// The lower bounds of this function is 0 and the upperbounds is 120
-    /// The area under the curve of the function is 1
-    fn get_percent_of_pop(&self, age: usize) -> f64;
-}
+    /// How often an active infection gets a chance to hurt this person
+    pub fn damage_interval(&self) -> &TimeUnit {
+        &self.damage_interval
+    }
+
+    /// Sets how often an active infection gets a chance to hurt this person
+    pub fn set_damage_interval(&mut self, interval: TimeUnit) {
+        self.damage_interval = interval;
+        self.ticks_since_damage_roll = 0;
+    }
+
+    /// The fraction of this person's max health that can be lost in a single tick
+    pub fn max_damage_fraction(&self) -> f64 {
+        self.max_damage_fraction
+    }
+
+    /// Sets the fraction of this person's max health that can be lost in a single tick
+    ///
+    /// # Panics
+    /// Panics if `fraction` is not within the range (0, 1]
+    pub fn set_max_damage_fraction(&mut self, fraction: f64) {
+        if fraction <= 0.0 || fraction > 1.0 {
+            panic!("Invalid max damage fraction: {}", fraction);
+        }
+        self.max_damage_fraction = fraction;
+    }
+
+    pub fn mortality_model(&self) -> MortalityModel {
+        self.mortality_model
+    }
+
+    /// Sets how infections caught from now on can kill this person
+    ///
+    /// # Panics
+    /// Panics if a case fatality rate is not within the range [0, 1]
+    pub fn set_mortality_model(&mut self, model: MortalityModel) {
+        if let MortalityModel::CaseFatality(rate) = model {
+            if !(0.0..=1.0).contains(&rate) {
+                panic!("Invalid case fatality rate: {}", rate);
+            }
+        }
+        self.mortality_model = model;
+    }
+
+    /// The immunity left behind by recovering from the mildest infection
+    pub fn base_immunity(&self) -> f64 {
+        self.base_immunity
+    }
+
+    /// Sets the immunity left behind by recovering from the mildest infection
+    ///
+    /// # Panics
+    /// Panics if `immunity` is not within the range [0, 1]
+    pub fn set_base_immunity(&mut self, immunity: f64) {
+        if !(0.0..=1.0).contains(&immunity) {
+            panic!("Invalid base immunity: {}", immunity);
+        }
+        self.base_immunity = immunity;
+    }
+
+    /// The fraction of immunity that carries over to a strain that shares nothing with the one
+    /// the person recovered from
+    pub fn cross_immunity(&self) -> f64 {
+        self.cross_immunity
+    }
+
+    /// Sets the fraction of immunity that carries over to a strain that shares nothing with the
+    /// one the person recovered from. Strains in between keep an amount of immunity in
+    /// proportion to how similar they are.
+    ///
+    /// # Panics
+    /// Panics if `fraction` is not within the range [0, 1]
+    pub fn set_cross_immunity(&mut self, fraction: f64) {
+        if !(0.0..=1.0).contains(&fraction) {
+            panic!("Invalid cross immunity: {}", fraction);
+        }
+        self.cross_immunity = fraction;
+    }
+
+    /// Reports this person's health dropping below the thresholds of `watch` from now on, or
+    /// stops reporting it if `watch` is `None`
+    pub fn watch_health(&mut self, watch: Option<Arc<HealthWatch>>) {
+        self.health_watch = watch;
+    }
+
+    /// At least one hp can always be lost, so people with little max health can still die
+    fn max_damage_per_tick(&self, max_health: u32) -> u32 {
+        u32::max(1, (max_health as f64 * self.max_damage_fraction) as u32)
+    }
+
+    fn damage_interval_ticks(&self) -> usize {
+        usize::max(1, usize::from(self.damage_interval.as_minutes()) * TICKS_TO_GAME_MIN)
+    }
+
+    /// The person's age in whole years, which stops counting up at the largest age a `u8` can
+    /// hold instead of wrapping back around to a newborn's health
+    fn get_age_years(&self) -> u8 {
+        let years = usize::from(self.age.lock().unwrap().0.as_years());
+        usize::min(years, u8::MAX as usize) as u8
+    }
+}
+
+impl PartialEq for Person {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Update for Person {
+    fn update_self(&mut self, delta_time: usize) {
+        self.update_with_aging(delta_time, true, &mut rand::thread_rng());
+    }
+}
+
+impl Person {
+    /// Makes the person `delta_time` ticks older, lowering their health if it's above the maximum
+    /// for their new age. Ticks that don't make up a whole game minute are carried over to the
+    /// next call, so a person ages by exactly the game time of all the ticks that passed.
+    fn age_by(&mut self, delta_time: usize) {
+        let previous_years = self.get_age_years();
+        self.ticks_since_aging += delta_time;
+        let minutes = self.ticks_since_aging / TICKS_TO_GAME_MIN;
+        self.ticks_since_aging %= TICKS_TO_GAME_MIN;
+        {
+            let mut age_guard = self.age.lock().unwrap();
+            *age_guard += Minutes(minutes);
+        }
+        let years = self.get_age_years();
+        self.birthday = if years > previous_years {
+            Some(years)
+        } else {
+            None
+        };
+
+        let max_health = Self::max_health(years, &self.sex, self.pre_existing_condition);
+        self.health_points.write().unwrap().cap(max_health);
+    }
+
+    /// Updates the person's infections and health, only aging them if `aging` is set so that the
+    /// aging can be done in bulk instead. Any health thresholds crossed during the update are
+    /// reported to the person's [HealthWatch]. Every roll of the update uses `rng`.
+    fn update_with_aging<R: Rng>(&mut self, delta_time: usize, aging: bool, rng: &mut R) {
+        let before = self.health_fraction();
+        let health_before = self.health_points.read().unwrap().get();
+        self.update_infections_and_health(delta_time, aging, rng);
+        let health_points = self.health_points.read().unwrap().get();
+        self.total_health_lost += u64::from(health_before.saturating_sub(health_points));
+        if let Some(watch) = &self.health_watch {
+            watch.check(self.id, before, self.health_fraction(), health_points);
+        }
+    }
+
+    fn update_infections_and_health<R: Rng>(
+        &mut self,
+        delta_time: usize,
+        aging: bool,
+        rng: &mut R,
+    ) {
+        let newly_recovered = {
+            // update infections
+            let mut output = Vec::new();
+            for infection in self.infections.lock().unwrap().values_mut() {
+                let was_recovered = infection.recovered();
+                infection.update_with_rng(delta_time, rng);
+                if !was_recovered && infection.recovered() {
+                    output.push((infection.get_pathogen().clone(), infection.fatal()));
+                }
+            }
+            output
+        };
+
+        if newly_recovered.iter().any(|(_, fatal)| *fatal) {
+            // a fatal infection ran its course
+            *self.health_points.write().unwrap() = HealthPoints::new(0);
+            return;
+        }
+
+        if aging {
+            self.age_by(delta_time);
+        }
+
+        if !newly_recovered.is_empty() {
+            // update recover status
+            if !self.infected() {
+                *self.condition.lock().unwrap() = Normal;
+            }
+            for (pathogen, _) in newly_recovered {
+                pathogen.perform_recovery_with(self, rng);
+            }
+        }
+
+        // update health points and condition
+        {
+            let max_health =
+                Self::max_health(self.get_age_years(), &self.sex, self.pre_existing_condition);
+            self.health_points.write().unwrap().cap(max_health);
+
+            if let MortalityModel::CaseFatality(_) = self.mortality_model {
+                // health declines with the progress of fatal infections, but never reaches 0
+                // before the infection ends
+                let progress = self
+                    .infections
+                    .lock()
+                    .unwrap()
+                    .values()
+                    .filter(|i| i.fatal() && !i.recovered())
+                    .map(|i| i.progress())
+                    .fold(0.0, f64::max);
+                let target = u32::max(1, (max_health as f64 * (1.0 - progress)) as u32);
+                let seeks_care = self.seeks_hospital_care();
+                let mut hp_guard = self.health_points.write().unwrap();
+                if progress > 0.0 {
+                    hp_guard.cap(target);
+                }
+                let mut condition = self.condition.lock().unwrap();
+                if *condition == Condition::Normal && *hp_guard < max_health / 4 && seeks_care {
+                    *condition = Condition::NeedsHospital;
+                }
+            } else if self.infected() {
+                // fatality is only rolled once per damage interval, no matter the tick size
+                self.ticks_since_damage_roll += delta_time;
+                let interval = self.damage_interval_ticks();
+                let rolls = self.ticks_since_damage_roll / interval;
+                self.ticks_since_damage_roll %= interval;
+                // death is always gradual, only a fraction of max health can be lost per damage
+                // roll
+                let max_damage = self.max_damage_per_tick(max_health);
+
+                for _ in 0..rolls {
+                    let mut damage_left = HealthPoints::new(max_damage);
+                    let hurt_rates = {
+                        // remove infection mutex as fast as possible
+                        self.infections
+                            .lock()
+                            .unwrap()
+                            .values()
+                            .filter(|i| {
+                                i.active_case() && roll_with(i.get_pathogen().fatality(), rng)
+                            })
+                            .map(|i| 1.0 / (1.0 - i.get_pathogen().severity()))
+                            .collect::<Vec<f64>>()
+                    };
+
+                    let seeks_care = self.seeks_hospital_care();
+                    for rate in hurt_rates {
+                        let change = &mut *self.condition.lock().unwrap();
+                        let mut hp_guard = self.health_points.write().unwrap();
+                        let damage = ((match change {
+                            Condition::Normal => 1.0,
+                            Condition::NeedsHospital => 3.0,
+                            Condition::Hospitalized => 2.0,
+                        }) * rate) as u32;
+                        let allowed = damage_left.saturating_damage(damage);
+                        let lost = hp_guard.saturating_damage(allowed);
+                        // only health that was actually lost counts against the cap
+                        damage_left.heal(allowed - lost, max_damage);
+
+                        if *change == Condition::Normal && seeks_care {
+                            match *hp_guard {
+                                hp if hp < max_health / 4 => {
+                                    *change = Condition::NeedsHospital;
+                                }
+                                _ => {}
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub struct PersonBuilder {
+    count: usize,
+}
+
+impl PersonBuilder {
+    pub fn new() -> Arc<Mutex<PersonBuilder>> {
+        Arc::new(Mutex::new(Self { count: 0 }))
+    }
+
+    /// The amount of people that have been created since the builder was made or last reset,
+    /// which is also the id the next person gets
+    pub fn current_count(&self) -> usize {
+        self.count
+    }
+
+    /// Starts handing out ids from zero again, so that the builder can be reused for another
+    /// scenario. This is only safe once nobody the builder created is still alive, since new
+    /// people would share their ids.
+    pub fn reset(&mut self) {
+        self.count = 0;
+    }
+
+    fn create_person(&mut self, age: Age, sex: Sex, pre_existing_condition: f64) -> Person {
+        let id = self.count;
+        self.count += 1;
+        Person::new(id, age, sex, pre_existing_condition)
+    }
+
+    fn copy_person(&mut self, person: &Person) -> Person {
+        let id = self.count;
+        self.count += 1;
+        person.deep_copy(id)
+    }
+}
+
+/// Locks a person for reading. A lock poisoned by a panic while the person was being updated is
+/// recovered, so one failed update doesn't stop the rest of the simulation.
+fn read_person(person: &Arc<RwLock<Person>>) -> RwLockReadGuard<'_, Person> {
+    person.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Locks a person for writing, recovering the lock if it was poisoned like [read_person]
+fn write_person(person: &Arc<RwLock<Person>>) -> RwLockWriteGuard<'_, Person> {
+    person.write().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Locks a person for writing if nobody else holds their lock, recovering the lock if it was
+/// poisoned like [read_person]
+fn try_write_person(person: &Arc<RwLock<Person>>) -> Option<RwLockWriteGuard<'_, Person>> {
+    match person.try_write() {
+        Ok(guard) => Some(guard),
+        Err(TryLockError::Poisoned(e)) => Some(e.into_inner()),
+        Err(TryLockError::WouldBlock) => None,
+    }
+}
+
+/// The settings that are set on a whole population at once, which are given to everyone born
+/// into it as well
+#[derive(Clone)]
+struct PersonSettings {
+    damage_interval: TimeUnit,
+    max_damage_fraction: f64,
+    mortality_model: MortalityModel,
+    base_immunity: f64,
+    cross_immunity: f64,
+    health_watch: Option<Arc<HealthWatch>>,
+}
+
+impl PersonSettings {
+    fn apply(&self, person: &mut Person) {
+        person.set_damage_interval(self.damage_interval.clone());
+        person.set_max_damage_fraction(self.max_damage_fraction);
+        person.set_mortality_model(self.mortality_model);
+        person.set_base_immunity(self.base_immunity);
+        person.set_cross_immunity(self.cross_immunity);
+        person.watch_health(self.health_watch.clone());
+    }
+}
+
+impl Default for PersonSettings {
+    fn default() -> Self {
+        Self {
+            damage_interval: DEFAULT_DAMAGE_INTERVAL,
+            max_damage_fraction: DEFAULT_MAX_DAMAGE_FRACTION,
+            mortality_model: MortalityModel::PerTick,
+            base_immunity: DEFAULT_BASE_IMMUNITY,
+            cross_immunity: DEFAULT_CROSS_IMMUNITY,
+            health_watch: None,
+        }
+    }
+}
+
+pub struct Population {
+    factory: Arc<Mutex<PersonBuilder>>,
+    people: Vec<Arc<RwLock<Person>>>,
+    original_pop: usize,
+    current_pop: usize,
+    infected: Vec<Arc<RwLock<Person>>>,
+    deceased: Vec<Arc<RwLock<Person>>>,
+    growth_rate: f64, // the fraction of the living born every game year
+    time_of_year: usize, // in ticks since the start of the game year
+    max_population: Option<usize>, // None if births aren't capped
+    pending_births: f64, // births owed from updates too short for a whole person
+    hospital_capacity: Option<usize>, // None if hospitals aren't modeled
+    triage_fatality: f64,
+    turned_away: Vec<Arc<RwLock<Person>>>, // people who sought care during the last allocation but got no bed
+    contact_scale: f64, // multiplies how many contacts everyone makes
+    contact_factors: HashMap<usize, f64>, // further multipliers from measures like lockdowns, by handle
+    next_contact_factor: usize, // the handle of the next contact factor
+    interaction_matrix: Option<Arc<InteractionMatrix>>, // records contacts between age groups
+    aging_interval: Option<usize>, // in ticks, when aging is done in bulk
+    unaged_ticks: usize, // ticks that passed since the last bulk aging
+    on_remove: Vec<Box<dyn Fn(usize) + Send + Sync>>, // called with the id of everyone removed
+    on_birthday: Vec<Box<dyn Fn(usize, u8) + Send + Sync>>, // called with the id and new age of everyone who had a birthday
+    infectious_dead: Vec<(Arc<RwLock<Person>>, usize)>, // bodies that still spread, with the ticks they have left
+    newborn_settings: PersonSettings, // the population wide settings given to everyone born
+    rng: Option<StdRng>, // when seeded, updates are sequential and use this for every roll
+}
+
+/// Represents the distribution of ages in a population
+pub trait PopulationDistribution {
+    /// Gets the percent of the population of an age
+    /// The lower bounds of this function is 0 and the upperbounds is [MAX_AGE]
+    /// The area under the curve of the function is 1
+    fn get_percent_of_pop(&self, age: usize) -> f64;
+}
 
 impl<F> PopulationDistribution for F
 where
@@ -356,11 +1337,29 @@ impl Population {
         population: usize,
         population_distribution: T,
     ) -> Self {
-        let mut pop = Vec::new();
+        Self::with_capacity(
+            builder,
+            growth_rate,
+            population,
+            population_distribution,
+            population,
+        )
+    }
+
+    /// Creates a population with room for at least `capacity` people, so large populations
+    /// aren't reallocated while they're being built
+    pub fn with_capacity<T: PopulationDistribution>(
+        builder: &Arc<Mutex<PersonBuilder>>,
+        growth_rate: f64,
+        population: usize,
+        population_distribution: T,
+        capacity: usize,
+    ) -> Self {
+        let mut pop = Vec::with_capacity(usize::max(population, capacity));
         let mut people_created = 0;
         let mut rng = rand::thread_rng();
 
-        for age in 0..121 {
+        for age in 0..=MAX_AGE as usize {
             let people_count =
                 (population as f64 * population_distribution.get_percent_of_pop(age)) as usize;
             for _ in 0..people_count {
@@ -394,13 +1393,95 @@ impl Population {
             people_created += 1;
         }
 
+        Population {
+            growth_rate,
+            ..Self::from_people(builder, pop)
+        }
+    }
+
+    /// Creates a population of `people` made by `builder`, which isn't growing and has
+    /// everything else at its default
+    fn from_people(
+        builder: &Arc<Mutex<PersonBuilder>>,
+        people: Vec<Arc<RwLock<Person>>>,
+    ) -> Self {
+        let size = people.len();
         Population {
             factory: builder.clone(),
-            people: pop,
-            original_pop: population,
-            current_pop: population,
+            people,
+            original_pop: size,
+            current_pop: size,
             infected: Vec::new(),
-            growth_rate,
+            deceased: Vec::new(),
+            growth_rate: 0.0,
+            time_of_year: 0,
+            max_population: None,
+            pending_births: 0.0,
+            hospital_capacity: None,
+            triage_fatality: DEFAULT_TRIAGE_FATALITY,
+            turned_away: Vec::new(),
+            contact_scale: 1.0,
+            contact_factors: HashMap::new(),
+            next_contact_factor: 0,
+            interaction_matrix: None,
+            aging_interval: None,
+            unaged_ticks: 0,
+            on_remove: Vec::new(),
+            on_birthday: Vec::new(),
+            infectious_dead: Vec::new(),
+            newborn_settings: PersonSettings::default(),
+            rng: None,
+        }
+    }
+
+    /// Creates an independent copy of the population in its current state, including every
+    /// infection, so that it can be simulated separately. Everyone in the copy gets a new id from
+    /// `new_builder`.
+    pub fn deep_copy(&self, new_builder: &Arc<Mutex<PersonBuilder>>) -> Population {
+        let mut copies = HashMap::new();
+        {
+            let mut builder = new_builder.lock().unwrap();
+            for person in self.people.iter().chain(&self.deceased) {
+                let person = read_person(person);
+                copies.insert(person.id, Arc::new(RwLock::new(builder.copy_person(&person))));
+            }
+        }
+        let copy_all = |people: &Vec<Arc<RwLock<Person>>>| {
+            people
+                .iter()
+                .map(|p| copies[&read_person(p).id].clone())
+                .collect::<Vec<_>>()
+        };
+
+        Population {
+            factory: new_builder.clone(),
+            people: copy_all(&self.people),
+            original_pop: self.original_pop,
+            current_pop: self.current_pop,
+            infected: copy_all(&self.infected),
+            deceased: copy_all(&self.deceased),
+            growth_rate: self.growth_rate,
+            time_of_year: self.time_of_year,
+            max_population: self.max_population,
+            pending_births: self.pending_births,
+            hospital_capacity: self.hospital_capacity,
+            triage_fatality: self.triage_fatality,
+            turned_away: copy_all(&self.turned_away),
+            contact_scale: self.contact_scale,
+            contact_factors: self.contact_factors.clone(),
+            next_contact_factor: self.next_contact_factor,
+            interaction_matrix: None,
+            aging_interval: self.aging_interval,
+            unaged_ticks: self.unaged_ticks,
+            on_remove: Vec::new(),
+            on_birthday: Vec::new(),
+            infectious_dead: self
+                .infectious_dead
+                .iter()
+                .map(|(p, ticks_left)| (copies[&read_person(p).id].clone(), *ticks_left))
+                .collect(),
+            newborn_settings: self.newborn_settings.clone(),
+            rng: self.rng.clone(),
         }
     }
 
@@ -409,7 +1490,7 @@ impl Population {
         self.get_everyone()
             .iter()
             .filter(|p| {
-                let person = &*p.read().unwrap();
+                let person = &*read_person(p);
                 person.recovered() || person.infected()
             })
             .count()
@@ -425,29 +1506,105 @@ impl Population {
 
             let person = self.people.get(person_id).unwrap().clone();
             {
-                let read = person.read().unwrap();
+                let read = read_person(&person);
                 if read.infected() || read.recovered() {
                     continue;
                 }
             }
-            if person.write().unwrap().infect(pathogen) {
+            if write_person(&person).infect(pathogen) {
                 self.infected.push(person);
                 break true;
             }
         }
     }
 
+    /// Finds the person with this id, whether they're living or deceased
+    pub fn find_by_id(&self, id: usize) -> Option<Arc<RwLock<Person>>> {
+        self.people
+            .iter()
+            .chain(&self.deceased)
+            .find(|p| read_person(p).id == id)
+            .cloned()
+    }
+
+    /// The oldest person living, or `None` if everyone has died
+    pub fn oldest(&self) -> Option<Arc<RwLock<Person>>> {
+        self.people
+            .iter()
+            .max_by_key(|p| usize::from(read_person(p).age.lock().unwrap().time_unit()))
+            .cloned()
+    }
+
+    /// The youngest person living, or `None` if everyone has died
+    pub fn youngest(&self) -> Option<Arc<RwLock<Person>>> {
+        self.people
+            .iter()
+            .min_by_key(|p| usize::from(read_person(p).age.lock().unwrap().time_unit()))
+            .cloned()
+    }
+
+    /// Everyone living whose age in whole years is within `range`, such as to vaccinate or
+    /// quarantine a single age group
+    pub fn people_in_age_range(&self, range: Range<u16>) -> Vec<Arc<RwLock<Person>>> {
+        self.people
+            .iter()
+            .filter(|p| {
+                let person = read_person(p);
+                person.alive() && range.contains(&u16::from(person.get_age_years()))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Infects the person with this id, as long as they're susceptible
+    ///
+    /// ###Return
+    /// Whether the person was found and became infected
+    pub fn infect_person(&mut self, id: usize, pathogen: &Arc<Pathogen>) -> bool {
+        let person = match self.find_by_id(id) {
+            None => return false,
+            Some(person) => person,
+        };
+        {
+            let read = read_person(&person);
+            if read.infected() || read.recovered() {
+                return false;
+            }
+        }
+        if write_person(&person).infect(pathogen) {
+            self.infected.push(person);
+            true
+        } else {
+            false
+        }
+    }
+
     pub fn remove_infected(&mut self, person: &Arc<RwLock<Person>>) -> Option<Arc<RwLock<Person>>> {
         let position = self
             .infected
             .iter()
-            .position(|p| p.read().unwrap().id == person.read().unwrap().id);
+            .position(|p| read_person(p).id == read_person(person).id);
         match position {
             None => None,
             Some(index) => Some(self.infected.remove(index)),
         }
     }
 
+    /// Rescans everyone and rebuilds the infected list, in case it drifted out of sync with the
+    /// actual state of the people. Anyone still shedding a recovered infection is kept, as they
+    /// would be by an update.
+    pub fn rebuild_infected(&mut self) {
+        self.infected = self
+            .people
+            .iter()
+            .filter(|p| {
+                let person = &*read_person(p);
+                person.infected() || person.shedding()
+            })
+            .cloned()
+            .collect();
+    }
+
     pub fn get_everyone(&self) -> &Vec<Arc<RwLock<Person>>> {
         &self.people
     }
@@ -456,6 +1613,12 @@ impl Population {
         &self.infected
     }
 
+    /// The bodies of the dead that are still within the post-mortem infectious period of a
+    /// pathogen they died with, which can keep spreading it to the living
+    pub fn get_infectious_dead(&self) -> impl Iterator<Item = &Arc<RwLock<Person>>> {
+        self.infectious_dead.iter().map(|(person, _)| person)
+    }
+
     pub fn get_total_population(&self) -> usize {
         self.current_pop
     }
@@ -464,48 +1627,727 @@ impl Population {
         self.original_pop
     }
 
-    pub fn age_a_year(&mut self) {
-        for _ in 0..1200 {
-            self.update(438);
+    /// Estimates the basic reproduction number of `pathogen` in this population, when each
+    /// infected person makes `contact_rate` contacts per game minute like in an
+    /// [InteractionController](person_behavior::interaction::InteractionController). Contacts
+    /// are scaled by the population's contact scale and by the average condition of the living,
+    /// and every infection is assumed to be infectious for the pathogen's average recovery time.
+    pub fn estimated_r0(&self, pathogen: &Pathogen, contact_rate: f64) -> f64 {
+        if self.people.is_empty() {
+            return 0.0;
         }
+        let condition = self
+            .people
+            .iter()
+            .map(|p| read_person(p).condition())
+            .sum::<f64>()
+            / self.people.len() as f64;
+        let minutes = usize::max(
+            pathogen.average_recovery_time(),
+            pathogen.minimum_infectious_period(),
+        );
+        contact_rate
+            * self.contact_scale()
+            * condition
+            * (1.0 - pathogen.severity())
+            * pathogen.catch_chance()
+            * minutes as f64
     }
-}
 
+    /// The fraction of the population that an epidemic of `pathogen` is expected to infect
+    /// before it dies out, from the final size equation `z = 1 - exp(-R0 * z)` with the
+    /// [Population::estimated_r0] for the default contact rate. Simulated attack rates can be
+    /// validated against it.
+    ///
+    /// ###Return
+    /// A fraction in the range [0, 1], which is 0 if the pathogen can't cause an epidemic
+    pub fn expected_final_size(&self, pathogen: &Pathogen) -> f64 {
+        let r0 = self.estimated_r0(pathogen, DEFAULT_CONTACT_RATE);
+        if r0 <= 1.0 {
+            return 0.0;
+        }
+        // the iteration moves away from the trivial root at 0, so starting from everyone being
+        // infected always converges on the epidemic's final size
+        let mut z = 1.0;
+        for _ in 0..1000 {
+            let next = 1.0 - (-r0 * z).exp();
+            if (next - z).abs() < 1e-12 {
+                return next;
+            }
+            z = next;
+        }
+        z
+    }
 
-impl ParallelUpdate<Arc<RwLock<Person>>> for Population {
-    fn parallel_update_self(&mut self, delta_time: usize) {
-        let mut infected_remove = Vec::new();
+    /// The mean behavioral activity of everyone in the population
+    pub fn average_behavioral_activity(&self) -> f64 {
+        if self.people.is_empty() {
+            return 0.0;
+        }
+        let total: f64 = self
+            .people
+            .iter()
+            .map(|p| read_person(p).behavioral_activity())
+            .sum();
+        total / self.people.len() as f64
+    }
 
-        for (pos, x) in self.get_infected().iter().enumerate() {
-            let person = &*x.read().expect("Should be able to get person");
-            if person.recovered() || person.dead() {
-                infected_remove.push(pos)
+    /// The mean age, in years, of everyone living
+    pub fn average_age(&self) -> f64 {
+        let minutes_per_year = usize::from(Years(1).into_minutes()) as f64;
+        let ages = self
+            .people
+            .iter()
+            .map(read_person)
+            .filter(|p| p.alive())
+            .map(|p| usize::from(p.age.lock().unwrap().time_unit().as_minutes()) as f64)
+            .collect::<Vec<f64>>();
+        if ages.is_empty() {
+            return 0.0;
+        }
+        ages.iter().sum::<f64>() / ages.len() as f64 / minutes_per_year
+    }
+
+    /// Counts everyone living in age bins that are `bin_size` years wide, starting from 0. Anyone
+    /// older than [MAX_AGE] is counted in the last bin.
+    ///
+    /// # Panics
+    /// Panics if `bin_size` is 0
+    pub fn age_histogram(&self, bin_size: usize) -> Vec<usize> {
+        if bin_size == 0 {
+            panic!("Invalid bin size: {}", bin_size);
+        }
+        let mut output = vec![0; MAX_AGE as usize / bin_size + 1];
+        for person in &self.people {
+            let person = read_person(person);
+            if person.alive() {
+                let bin = usize::min(person.get_age_years() as usize / bin_size, output.len() - 1);
+                output[bin] += 1;
             }
         }
+        output
+    }
 
-        infected_remove.sort_by(|a, b| a.cmp(b).reverse());
-        for r in infected_remove {
-            self.infected.remove(r);
+    /// Splits the range [0, 1] of pre-existing conditions into `bins` equally wide buckets, and
+    /// gets the fraction of the people in each bucket that were ever infected, including the
+    /// deceased. Buckets are given as their lower bound with their attack rate, and empty buckets
+    /// have an attack rate of 0.
+    ///
+    /// # Panics
+    /// Panics if `bins` is 0
+    pub fn attack_rate_by_condition(&self, bins: usize) -> Vec<(f64, f64)> {
+        if bins == 0 {
+            panic!("Invalid bin count: {}", bins);
+        }
+        let mut totals = vec![0usize; bins];
+        let mut infected = vec![0usize; bins];
+        for person in self.people.iter().chain(&self.deceased) {
+            let person = read_person(person);
+            let bin = usize::min((person.pre_existing_condition * bins as f64) as usize, bins - 1);
+            totals[bin] += 1;
+            if !person.never_infected() {
+                infected[bin] += 1;
+            }
         }
+        (0..bins)
+            .map(|bin| {
+                let rate = match totals[bin] {
+                    0 => 0.0,
+                    total => infected[bin] as f64 / total as f64,
+                };
+                (bin as f64 / bins as f64, rate)
+            })
+            .collect()
+    }
 
-        let mut full_remove = Vec::new();
-        for (pos, x) in self.get_everyone().iter().enumerate() {
-            let person = &*x.read().expect("Should be able to get person");
+    /// Counts how many active infections carry each distinct strain
+    pub fn circulating_strains(&self) -> HashMap<Pathogen, usize> {
+        let mut output = HashMap::new();
+        for person in &self.infected {
+            let person = read_person(person);
             if person.dead() {
-                full_remove.push(pos)
+                continue;
+            }
+            for infection in person.infections.lock().unwrap().values() {
+                if !infection.recovered() {
+                    *output.entry((**infection.get_pathogen()).clone()).or_insert(0) += 1;
+                }
             }
         }
+        output
+    }
 
-        full_remove.sort_by(|a, b| a.cmp(b).reverse());
-        for r in full_remove {
-            self.people.remove(r);
-            self.current_pop -= 1;
+    /// Applies `f` to every living person in parallel
+    ///
+    /// Each person is locked for writing while `f` runs on them. A lock poisoned by an earlier
+    /// panic is recovered instead of propagating the panic.
+    pub fn par_for_each<F>(&self, f: F)
+    where
+        F: Fn(&mut Person) + Sync,
+    {
+        self.people.par_iter().for_each(|person| {
+            f(&mut write_person(person))
+        });
+    }
+
+    /// Counts how many people are in each SEIR compartment, including everyone who has died
+    pub fn compartments(&self) -> CompartmentCounts {
+        let mut output = CompartmentCounts::default();
+        for person in &self.people {
+            output.add(read_person(person).compartment());
         }
+        output.dead += self.deceased.len();
+        output
+    }
+
+    /// Gets the amount of people in each compartment and how many have ever been infected, reading
+    /// each person only once
+    pub fn statistics(&self) -> PopulationStats {
+        let mut output = self.statistics_of(|_| true);
+        output.original = self.original_pop;
+        output
+    }
+
+    /// Assigns everyone with one of the `ids`, living or deceased, to `cohort`
+    ///
+    /// ###Return
+    /// The amount of people who were found and assigned
+    pub fn assign_cohort(&self, ids: &[usize], cohort: &str) -> usize {
+        let mut assigned = 0;
+        for person in self.people.iter().chain(&self.deceased) {
+            let mut person = write_person(person);
+            if ids.contains(&person.id) {
+                person.set_cohort(Some(cohort.to_string()));
+                assigned += 1;
+            }
+        }
+        assigned
+    }
+
+    /// Gets the statistics of only the people in `cohort`, where the original size is the amount
+    /// of people in the cohort
+    pub fn cohort_outcomes(&self, cohort: &str) -> PopulationStats {
+        let mut output = self.statistics_of(|p| p.cohort() == Some(cohort));
+        output.original = output.total();
+        output
+    }
+
+    /// Gets the statistics of everyone, living or deceased, who `include` accepts
+    fn statistics_of<F: Fn(&Person) -> bool>(&self, include: F) -> PopulationStats {
+        let mut output = PopulationStats::default();
+        for person in self.people.iter().chain(&self.deceased) {
+            let person = read_person(person);
+            if !include(&person) {
+                continue;
+            }
+            match person.compartment() {
+                Compartment::Susceptible => output.susceptible += 1,
+                Compartment::Exposed => output.exposed += 1,
+                Compartment::Infectious => output.infectious += 1,
+                Compartment::Recovered => output.recovered += 1,
+                Compartment::Dead => output.dead += 1,
+            }
+            if !person.never_infected() {
+                output.ever_infected += 1;
+            }
+        }
+        output
+    }
+
+    /// Returns the population to the state it was in before any infections, bringing back anyone
+    /// who died. Ids and demographics are kept.
+    pub fn reset(&mut self) {
+        self.current_pop += self.deceased.len();
+        self.people.append(&mut self.deceased);
+        self.people.sort_by_key(|p| read_person(p).id);
+        for person in &self.people {
+            write_person(person).reset();
+        }
+        self.infected.clear();
+        self.turned_away.clear();
+        self.infectious_dead.clear();
+    }
+
+    /// Sets how often active infections get a chance to hurt each person
+    pub fn set_damage_interval(&mut self, interval: TimeUnit) {
+        self.newborn_settings.damage_interval = interval.clone();
+        for person in &self.people {
+            write_person(person).set_damage_interval(interval.clone());
+        }
+    }
+
+    /// How far into the game year the population is, which starts at the beginning of the year
+    pub fn time_of_year(&self) -> TimeUnit {
+        Minutes(self.time_of_year / TICKS_TO_GAME_MIN)
+    }
+
+    /// Sets how far into the game year the population is, wrapping around if `time` is longer
+    /// than a year
+    pub fn set_time_of_year(&mut self, time: TimeUnit) {
+        let ticks = usize::from(time.into_minutes()) * TICKS_TO_GAME_MIN;
+        self.time_of_year = ticks % Self::year_ticks();
+    }
+
+    fn year_ticks() -> usize {
+        usize::from(Years(1).into_minutes()) * TICKS_TO_GAME_MIN
+    }
+
+    /// The most people that can be alive at once, or `None` if births aren't capped
+    pub fn max_population(&self) -> Option<usize> {
+        self.max_population
+    }
+
+    /// Sets the most people that can be alive at once. Once the cap is reached no one else is
+    /// born, but nobody already alive is removed if the cap is lowered below the population.
+    pub fn set_max_population(&mut self, max_population: Option<usize>) {
+        self.max_population = max_population;
+    }
+
+    /// Adds the newborns for `delta_time` ticks of growth, stopping once the population reaches
+    /// [Population::max_population]
+    ///
+    /// ###Return
+    /// The amount of people born
+    pub fn apply_growth(&mut self, delta_time: usize) -> usize {
+        self.with_rng(|pop, rng| pop.apply_growth_with(delta_time, rng))
+    }
+
+    fn apply_growth_with(&mut self, delta_time: usize, rng: &mut dyn RngCore) -> usize {
+        if self.growth_rate <= 0.0 {
+            return 0;
+        }
+        self.pending_births += self.current_pop as f64 * self.growth_rate * delta_time as f64
+            / Self::year_ticks() as f64;
+        let mut births = self.pending_births as usize;
+        self.pending_births -= births as f64;
+        if let Some(max_population) = self.max_population {
+            births = usize::min(births, max_population.saturating_sub(self.current_pop));
+        }
+
+        let mut builder = self.factory.lock().unwrap();
+        for _ in 0..births {
+            let mut person = builder.create_person(
+                Age::new(0, 0, 0),
+                if rng.gen_bool(0.5) { Male } else { Female },
+                1.0,
+            );
+            self.newborn_settings.apply(&mut person);
+            self.people.push(Arc::new(RwLock::new(person)));
+        }
+        self.current_pop += births;
+        births
+    }
+
+    /// How much the contacts everyone makes are scaled by, such as during a lockdown, including
+    /// every contact factor
+    pub fn contact_scale(&self) -> f64 {
+        self.contact_factors
+            .values()
+            .fold(self.contact_scale, |scale, factor| scale * factor)
+    }
+
+    /// Scales the contacts everyone makes, on top of which every contact factor still applies
+    ///
+    /// # Panics
+    /// Panics if `scale` is negative
+    pub fn set_contact_scale(&mut self, scale: f64) {
+        if scale < 0.0 {
+            panic!("Invalid contact scale: {}", scale);
+        }
+        self.contact_scale = scale;
+    }
+
+    /// Scales the contacts everyone makes by `factor` until it's removed, such as for a lockdown.
+    /// Every factor is kept apart, so measures that overlap can each undo only their own.
+    ///
+    /// ###Return
+    /// The handle to remove the factor with
+    ///
+    /// # Panics
+    /// Panics if `factor` is negative
+    pub fn add_contact_factor(&mut self, factor: f64) -> usize {
+        if factor < 0.0 {
+            panic!("Invalid contact factor: {}", factor);
+        }
+        let handle = self.next_contact_factor;
+        self.next_contact_factor += 1;
+        self.contact_factors.insert(handle, factor);
+        handle
+    }
+
+    /// Removes the contact factor with this handle
+    ///
+    /// ###Return
+    /// Whether the factor was still applied
+    pub fn remove_contact_factor(&mut self, handle: usize) -> bool {
+        self.contact_factors.remove(&handle).is_some()
+    }
+
+    /// How often everyone is aged at once, if aging is batched
+    pub fn aging_interval(&self) -> Option<TimeUnit> {
+        self.aging_interval.map(|ticks| Minutes(ticks / TICKS_TO_GAME_MIN))
+    }
+
+    /// Batches aging, so that updates only fully update the infected and everyone else is aged
+    /// at once every `interval`. Uninfected people do little besides aging, so this saves most of
+    /// the work of an update in large populations. Birthdays are only noticed on the update that
+    /// ages everyone, and last until the next one. Setting `None` goes back to updating everyone
+    /// every update, catching up on any aging that was still pending.
+    ///
+    /// Only people in [Population::get_infected] are updated, so anyone infected from outside of
+    /// the population's own methods has to be added with [Population::rebuild_infected].
+    ///
+    /// # Panics
+    /// Panics if `interval` is shorter than a minute
+    pub fn set_aging_interval(&mut self, interval: Option<TimeUnit>) {
+        match interval {
+            Some(interval) => {
+                let minutes = usize::from(interval.into_minutes());
+                if minutes == 0 {
+                    panic!("Invalid aging interval: {} minutes", minutes);
+                }
+                self.aging_interval = Some(minutes * TICKS_TO_GAME_MIN);
+            }
+            None => {
+                self.aging_interval = None;
+                self.apply_pending_aging();
+            }
+        }
+    }
+
+    /// Starts counting the contacts made between age groups that are `bin_size` years wide, and
+    /// gets the recorder that they are counted in
+    ///
+    /// # Panics
+    /// Panics if `bin_size` is 0
+    pub fn record_interactions(&mut self, bin_size: usize) -> Arc<InteractionMatrix> {
+        let matrix = Arc::new(InteractionMatrix::new(bin_size));
+        self.interaction_matrix = Some(matrix.clone());
+        matrix
+    }
+
+    /// Gets the recorder that contacts between age groups are counted in, if they're recorded
+    pub fn interaction_recorder(&self) -> Option<&Arc<InteractionMatrix>> {
+        self.interaction_matrix.as_ref()
+    }
+
+    /// Gets the contacts made between age groups since recording started, as
+    /// `contacts[infected group][contacted group]`
+    pub fn interaction_matrix(&self) -> Option<Vec<Vec<usize>>> {
+        self.interaction_matrix.as_ref().map(|m| m.contacts())
+    }
+
+    /// Sets the fraction of max health each person can lose in a single tick
+    pub fn set_max_damage_fraction(&mut self, fraction: f64) {
+        self.newborn_settings.max_damage_fraction = fraction;
+        for person in &self.people {
+            write_person(person).set_max_damage_fraction(fraction);
+        }
+    }
+
+    /// Sets the immunity each person is left with after recovering from the mildest infection
+    pub fn set_base_immunity(&mut self, immunity: f64) {
+        self.newborn_settings.base_immunity = immunity;
+        for person in &self.people {
+            write_person(person).set_base_immunity(immunity);
+        }
+    }
+
+    /// Sets the fraction of each person's immunity that carries over to a completely different
+    /// strain of the pathogen they recovered from
+    pub fn set_cross_immunity(&mut self, fraction: f64) {
+        self.newborn_settings.cross_immunity = fraction;
+        for person in &self.people {
+            write_person(person).set_cross_immunity(fraction);
+        }
+    }
+
+    /// Sets how infections caught from now on can kill each person
+    pub fn set_mortality_model(&mut self, model: MortalityModel) {
+        self.newborn_settings.mortality_model = model;
+        for person in &self.people {
+            write_person(person).set_mortality_model(model);
+        }
+    }
+
+    /// The amount of hospital beds, or `None` if hospitals aren't modeled
+    pub fn hospital_capacity(&self) -> Option<usize> {
+        self.hospital_capacity
+    }
+
+    /// Sets the amount of hospital beds available to the population
+    pub fn set_hospital_capacity(&mut self, beds: Option<usize>) {
+        self.hospital_capacity = beds;
+    }
+
+    /// The chance that an untreated person who needs a hospital dies each time triage is applied
+    pub fn triage_fatality(&self) -> f64 {
+        self.triage_fatality
+    }
+
+    /// Sets the chance that an untreated person who needs a hospital dies each time triage is
+    /// applied
+    ///
+    /// # Panics
+    /// Panics if `fatality` is not within the range [0, 1]
+    pub fn set_triage_fatality(&mut self, fatality: f64) {
+        if !(0.0..=1.0).contains(&fatality) {
+            panic!("Invalid triage fatality: {}", fatality);
+        }
+        self.triage_fatality = fatality;
+    }
+
+    /// People who need a hospital seek care with a chance equal to their severity, and are
+    /// admitted into any free beds. Anyone who seeks care but doesn't get a bed is turned away
+    /// until the next allocation.
+    ///
+    /// ###Return
+    /// The amount of people admitted
+    pub fn allocate_hospital_beds(&mut self) -> usize {
+        self.with_rng(Self::allocate_hospital_beds_with)
+    }
+
+    fn allocate_hospital_beds_with(&mut self, mut rng: &mut dyn RngCore) -> usize {
+        self.turned_away.clear();
+        let capacity = match self.hospital_capacity {
+            None => return 0,
+            Some(capacity) => capacity,
+        };
+        let occupied = self
+            .people
+            .iter()
+            .filter(|p| *read_person(p).condition.lock().unwrap() == Condition::Hospitalized)
+            .count();
+        let mut free = capacity.saturating_sub(occupied);
+        let mut admitted = 0;
+        for arc in &self.people {
+            let person = read_person(arc);
+            if *person.condition.lock().unwrap() != Condition::NeedsHospital
+                || !roll_with(person.severity(), &mut rng)
+            {
+                continue;
+            }
+            if free == 0 {
+                self.turned_away.push(arc.clone());
+            } else {
+                *person.condition.lock().unwrap() = Condition::Hospitalized;
+                free -= 1;
+                admitted += 1;
+            }
+        }
+        admitted
+    }
+
+    /// Everyone who was turned away from a hospital during the last allocation goes untreated,
+    /// and has a chance of dying outright
+    ///
+    /// ###Return
+    /// The amount of people who died
+    pub fn apply_triage_mortality(&mut self) -> usize {
+        self.with_rng(Self::apply_triage_mortality_with)
+    }
+
+    fn apply_triage_mortality_with(&mut self, mut rng: &mut dyn RngCore) -> usize {
+        let mut deaths = 0;
+        for person in &self.turned_away {
+            let person = read_person(person);
+            let untreated = *person.condition.lock().unwrap() == Condition::NeedsHospital;
+            if untreated && person.infected() && roll_with(self.triage_fatality, &mut rng) {
+                *person.health_points.write().unwrap() = HealthPoints::new(0);
+                deaths += 1;
+            }
+        }
+        deaths
+    }
+
+    /// Updates the population through a whole game year, split into 1200 updates
+    pub fn age_a_year(&mut self) {
+        let ticks = usize::from(Years(1).into_minutes()) * TICKS_TO_GAME_MIN;
+        for _ in 0..1200 {
+            self.update(ticks / 1200);
+        }
+    }
+}
+
+
+impl ParallelUpdate<Arc<RwLock<Person>>> for Population {
+    fn parallel_update_self(&mut self, delta_time: usize) {
+        self.time_of_year = (self.time_of_year + delta_time) % Self::year_ticks();
+        for (_, ticks_left) in &mut self.infectious_dead {
+            *ticks_left = ticks_left.saturating_sub(delta_time);
+        }
+        self.infectious_dead.retain(|(_, ticks_left)| *ticks_left > 0);
+        self.apply_growth(delta_time);
+        self.allocate_hospital_beds();
+        self.apply_triage_mortality();
     }
 
     fn parallel_get_update_children(&mut self) -> Vec<&mut Arc<RwLock<Person>>> {
         self.people.iter_mut().map(|arc| arc).collect()
     }
+
+    fn parallel_update(&mut self, delta_time: usize) {
+        self.update_without_cleanup(delta_time);
+        self.cleanup();
+    }
+}
+
+impl Population {
+    /// Updates everyone without removing the dead or the recovered afterwards, so the population
+    /// can be inspected before [Population::cleanup] is run
+    pub fn update_without_cleanup(&mut self, delta_time: usize) {
+        if self.aging_interval.is_some() {
+            self.update_infected_only(delta_time);
+            return;
+        }
+        self.parallel_update_self(delta_time);
+        if self.deterministic() {
+            self.with_rng(|pop, mut rng| {
+                for person in &pop.people {
+                    write_person(person).update_with_aging(delta_time, true, &mut rng);
+                }
+            });
+        } else {
+            self.parallel_get_update_children()
+                .par_iter_mut()
+                .for_each(|child| child.update(delta_time));
+        }
+        self.report_birthdays();
+    }
+
+    /// The fast path used while aging is done in bulk. Only the infected are fully updated, and
+    /// everyone is aged at once whenever enough time has built up.
+    fn update_infected_only(&mut self, delta_time: usize) {
+        self.parallel_update_self(delta_time);
+        self.unaged_ticks += delta_time;
+        if self.unaged_ticks >= self.aging_interval.unwrap_or(0) {
+            self.apply_pending_aging();
+            self.report_birthdays();
+        }
+        if self.deterministic() {
+            self.with_rng(|pop, mut rng| {
+                for person in &pop.infected {
+                    write_person(person).update_with_aging(delta_time, false, &mut rng);
+                }
+            });
+        } else {
+            self.infected.par_iter().for_each(|person| {
+                write_person(person).update_with_aging(delta_time, false, &mut rand::thread_rng())
+            });
+        }
+    }
+
+    /// Makes every following update sequential and driven by a rng seeded with `seed`, so that
+    /// updating the same population always turns out the same way
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = Some(StdRng::seed_from_u64(seed));
+    }
+
+    /// Whether updates are sequential and reproducible
+    pub fn deterministic(&self) -> bool {
+        self.rng.is_some()
+    }
+
+    /// Runs `f` with the population's seeded rng, or with the thread's rng if it wasn't seeded
+    fn with_rng<T>(&mut self, f: impl FnOnce(&mut Self, &mut dyn RngCore) -> T) -> T {
+        match self.rng.take() {
+            Some(mut rng) => {
+                let output = f(self, &mut rng);
+                self.rng = Some(rng);
+                output
+            }
+            None => f(self, &mut rand::thread_rng()),
+        }
+    }
+
+    /// Ages everyone by the ticks that passed since the last bulk aging
+    fn apply_pending_aging(&mut self) {
+        let ticks = std::mem::replace(&mut self.unaged_ticks, 0);
+        if ticks > 0 {
+            self.people
+                .par_iter()
+                .for_each(|person| write_person(person).age_by(ticks));
+        }
+    }
+
+    /// Removes everyone who is no longer infected from the infected, and moves the dead into the
+    /// deceased
+    pub fn cleanup(&mut self) {
+        let mut infected_remove = Vec::new();
+
+        for (pos, x) in self.get_infected().iter().enumerate() {
+            let person = &*read_person(x);
+            if (person.recovered() && !person.shedding()) || person.dead() {
+                infected_remove.push(pos)
+            }
+        }
+
+        infected_remove.sort_by(|a, b| a.cmp(b).reverse());
+        for r in infected_remove {
+            self.infected.remove(r);
+        }
+
+        let mut full_remove = Vec::new();
+        for (pos, x) in self.get_everyone().iter().enumerate() {
+            let person = &*read_person(x);
+            if person.dead() {
+                full_remove.push(pos)
+            }
+        }
+
+        full_remove.sort_by(|a, b| a.cmp(b).reverse());
+        for r in full_remove {
+            let person = self.people.remove(r);
+            let id = read_person(&person).id;
+            for callback in &self.on_remove {
+                callback(id);
+            }
+            let period = read_person(&person).post_mortem_infectious_period();
+            if period > 0 {
+                self.infectious_dead
+                    .push((person.clone(), period * TICKS_TO_GAME_MIN));
+            }
+            self.deceased.push(person);
+            self.current_pop -= 1;
+        }
+    }
+
+    /// Registers a callback that is called with the id of everyone who is removed from the
+    /// living, so that structures indexing people from outside of the population can be kept up
+    /// to date. Callbacks aren't carried over by [Population::deep_copy].
+    pub fn on_remove(&mut self, callback: Box<dyn Fn(usize) + Send + Sync>) {
+        self.on_remove.push(callback);
+    }
+
+    /// Registers a callback that is called with the id of everyone who entered a new year of age
+    /// during an update, along with the age they turned. Callbacks aren't carried over by
+    /// [Population::deep_copy].
+    pub fn on_birthday(&mut self, callback: Box<dyn Fn(usize, u8) + Send + Sync>) {
+        self.on_birthday.push(callback);
+    }
+
+    fn report_birthdays(&self) {
+        if self.on_birthday.is_empty() {
+            return;
+        }
+        for person in &self.people {
+            let person = read_person(person);
+            if let Some(years) = person.birthday() {
+                for callback in &self.on_birthday {
+                    callback(person.id, years);
+                }
+            }
+        }
+    }
+
+    /// Reports everyone's health dropping below the thresholds of `watch`, or stops reporting it
+    /// if `watch` is `None`
+    pub fn watch_health(&mut self, watch: Option<Arc<HealthWatch>>) {
+        self.newborn_settings.health_watch = watch.clone();
+        for person in self.get_everyone() {
+            write_person(person).watch_health(watch.clone());
+        }
+    }
 }
 
 pub struct UniformDistribution {
@@ -529,23 +2371,108 @@ impl PopulationDistribution for UniformDistribution {
     }
 }
 
+/// The amount of years in every band of a [PyramidDistribution]
+pub const PYRAMID_BAND_YEARS: usize = 5;
+
+///
+/// A population pyramid, given as the percentage of a region's population in every 5 year age
+/// band, starting with the band of 0 to 4 year olds
+///
+/// The percentages don't have to add up to exactly 100, they're scaled so that they do. Anyone
+/// older than the last band is left out.
+pub struct PyramidDistribution {
+    name: String,
+    bands: Vec<f64>, // fraction of the population in each band
+}
+
+impl PyramidDistribution {
+    /// # Panics
+    /// Panics if there are no bands, if any percentage is negative, or if they add up to 0
+    pub fn new(name: &str, percentages: &[f64]) -> Self {
+        let total: f64 = percentages.iter().sum();
+        if percentages.iter().any(|p| *p < 0.0) || total <= 0.0 {
+            panic!("Invalid age band percentages: {:?}", percentages);
+        }
+        Self {
+            name: name.to_string(),
+            bands: percentages.iter().map(|p| p / total).collect(),
+        }
+    }
+
+    /// A young, fast growing region, where almost half of everyone is a child
+    pub fn young() -> Self {
+        Self::new(
+            "Young",
+            &[
+                17.5, 15.0, 12.5, 10.5, 9.0, 7.5, 6.0, 5.0, 4.0, 3.5, 2.8, 2.2, 1.6, 1.2, 0.8, 0.5,
+                0.3, 0.1,
+            ],
+        )
+    }
+
+    /// An aging region with a low birth rate, where the largest bands are past middle age
+    pub fn aging() -> Self {
+        Self::new(
+            "Aging",
+            &[
+                3.8, 4.0, 4.3, 4.6, 5.0, 5.2, 5.6, 6.1, 7.0, 7.4, 6.6, 6.1, 6.3, 7.5, 6.6, 5.6,
+                4.6, 3.1, 1.6, 0.6,
+            ],
+        )
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The fraction of the population in each band
+    pub fn bands(&self) -> &[f64] {
+        &self.bands
+    }
+}
+
+impl PopulationDistribution for PyramidDistribution {
+    fn get_percent_of_pop(&self, age: usize) -> f64 {
+        match self.bands.get(age / PYRAMID_BAND_YEARS) {
+            None => 0.0,
+            Some(fraction) => fraction / PYRAMID_BAND_YEARS as f64,
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::borrow::{Borrow, BorrowMut};
     use std::collections::HashSet;
     use std::rc::Rc;
-    use std::sync::{Arc, Mutex};
+    use std::sync::{Arc, Mutex, RwLock};
     use std::thread;
 
-    use crate::game::{Age, Update};
+    use rand::rngs::mock::StepRng;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use structure::graph::Graph;
+    use structure::time::Time;
+    use structure::time::TimeUnit::{Days, Hours, Minutes, Years};
+
+    use crate::game::{Age, TICKS_TO_GAME_MIN, Update};
+    use crate::game::pathogen::infection::Infection;
     use crate::game::pathogen::Pathogen;
-    use crate::game::pathogen::symptoms::base::cheat::{CustomFatality, Undying};
-    use crate::game::pathogen::symptoms::Symp;
+    use crate::game::pathogen::symptoms::base::cheat::{
+        CustomCatchChance, CustomFatality, CustomSeverity, Undying,
+    };
+    use crate::game::pathogen::symptoms::{Symp, SymptomMapBuilder};
     use crate::game::pathogen::types::{PathogenType, Virus};
     use crate::game::population::{
-        Person, PersonBuilder, Population, PopulationDistribution, UniformDistribution,
+        read_person, Compartment, Condition, HealthPoints, HealthWatch, InteractionOutcome,
+        MortalityModel, Person, PersonBuilder, Population, PopulationDistribution, PopulationStats,
+        PyramidDistribution, UniformDistribution, MAX_AGE,
     };
-    use crate::game::population::Sex::Male;
+    use crate::game::population::person_behavior::interaction::InteractionController;
+    use crate::game::population::person_behavior::transmission::{Transmission, TransmissionTree};
+    use crate::game::population::person_behavior::Controller;
+    use crate::game::population::Sex::{Female, Male};
 
     #[test]
     fn can_transfer() {
@@ -570,37 +2497,110 @@ mod test {
         }
     }
 
-    /// Tests to see if creating multiple populations at once works fine and all ids are unique
     #[test]
-    fn concurrent_population_creation_id_check() {
-        let builder = PersonBuilder::new();
-        let vec = Arc::new(Mutex::new(Vec::new()));
-        let mut handles = Vec::new();
-        for _ in 0..10 {
-            let vec_copy = vec.clone();
-            let build_clone = builder.clone();
-            handles.push(thread::spawn(move || {
-                let pop = Population::new(&build_clone, 0.0, 100, UniformDistribution::new(20, 55));
-                if let Ok(mut g) = vec_copy.lock() {
-                    for ref people in pop.people {
-                        g.push(people.clone())
-                    }
-                }
-            }));
+    fn co_infection_progresses_and_transfers() {
+        let mut person_a = Person::new(0, Age::new(17, 0, 0), Male, 1.00);
+        let mut person_b = Person::new(1, Age::new(17, 0, 0), Male, 1.00);
+        let mut pathogens = Vec::new();
+        for name in &["A", "B"] {
+            let mut p = Virus.create_pathogen(name, 0);
+            p.acquire_symptom(&Undying.get_symptom(), None);
+            p.acquire_symptom(&CustomCatchChance(99.0).get_symptom(), None);
+            pathogens.push(Arc::new(p));
         }
 
-        for handle in handles {
-            handle.join().unwrap();
+        for pathogen in &pathogens {
+            assert!(person_a.infect(pathogen));
         }
+        assert!(!person_a.infect(&pathogens[0]), "Can't catch the same pathogen twice");
+        assert_eq!(person_a.infections().len(), 2);
 
-        let vector = vec
-            .lock()
-            .expect("Should be able to access the Vector as all threads have been waited")
-            .to_owned();
-        assert_eq!(
-            vector.len(),
-            1000,
-            "1000 people should have been created concurrently, but {} was instead",
+        while !person_a.infections().iter().all(|i| i.active_case()) {
+            person_a.update(20);
+        }
+        for infection in person_a.infections() {
+            assert!(*infection.infection_age().time_unit() > Minutes(0));
+        }
+
+        for _ in 0..1000 {
+            person_a.interact_with(&mut person_b);
+            if person_b.infections().len() == 2 {
+                break;
+            }
+        }
+        for pathogen in &pathogens {
+            assert!(
+                person_b.infected_with(pathogen.name()),
+                "{:?} was never transferred",
+                pathogen
+            );
+        }
+    }
+
+    #[test]
+    fn recovered_shedders_transmit_within_window() {
+        let mut shedder = Person::new(0, Age::new(17, 0, 0), Male, 1.00);
+        let mut p = Virus.create_pathogen("Test", 0);
+        p.acquire_symptom(&CustomCatchChance(99.0).get_symptom(), None);
+        p.set_shedding(Days(1), 1.0);
+        assert!(shedder.infect(&Arc::new(p)));
+
+        while !shedder.recovered() {
+            shedder.update(20 * 60);
+        }
+        assert!(shedder.shedding());
+
+        let mut during = Person::new(1, Age::new(17, 0, 0), Male, 1.00);
+        for _ in 0..100 {
+            if shedder.interact_with(&mut during) {
+                break;
+            }
+        }
+        assert!(during.infected(), "Shedder should infect during the window");
+
+        for _ in 0..25 {
+            shedder.update(20 * 60);
+        }
+        assert!(!shedder.shedding());
+
+        let mut after = Person::new(2, Age::new(17, 0, 0), Male, 1.00);
+        for _ in 0..100 {
+            shedder.interact_with(&mut after);
+        }
+        assert!(after.never_infected(), "Shedder should not infect after the window");
+    }
+
+    /// Tests to see if creating multiple populations at once works fine and all ids are unique
+    #[test]
+    fn concurrent_population_creation_id_check() {
+        let builder = PersonBuilder::new();
+        let vec = Arc::new(Mutex::new(Vec::new()));
+        let mut handles = Vec::new();
+        for _ in 0..10 {
+            let vec_copy = vec.clone();
+            let build_clone = builder.clone();
+            handles.push(thread::spawn(move || {
+                let pop = Population::new(&build_clone, 0.0, 100, UniformDistribution::new(20, 55));
+                if let Ok(mut g) = vec_copy.lock() {
+                    for ref people in pop.people {
+                        g.push(people.clone())
+                    }
+                }
+            }));
+        }
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let vector = vec
+            .lock()
+            .expect("Should be able to access the Vector as all threads have been waited")
+            .to_owned();
+        assert_eq!(
+            vector.len(),
+            1000,
+            "1000 people should have been created concurrently, but {} was instead",
             vector.len()
         );
         let mut found = [false; 1000];
@@ -622,6 +2622,32 @@ mod test {
         );
     }
 
+    #[test]
+    fn reset_builder_restarts_ids() {
+        let builder = PersonBuilder::new();
+        let ids = |pop: &Population| {
+            let mut ids = pop
+                .get_everyone()
+                .iter()
+                .map(|p| read_person(p).id())
+                .collect::<Vec<_>>();
+            ids.sort();
+            ids
+        };
+
+        let first = Population::new(&builder, 0.0, 50, UniformDistribution::new(20, 60));
+        assert_eq!(builder.lock().unwrap().current_count(), 50);
+        let second = Population::new(&builder, 0.0, 50, UniformDistribution::new(20, 60));
+        assert_eq!(ids(&second), (50..100).collect::<Vec<_>>());
+        drop((first, second));
+
+        builder.lock().unwrap().reset();
+        assert_eq!(builder.lock().unwrap().current_count(), 0);
+        let reused = Population::new(&builder, 0.0, 50, UniformDistribution::new(20, 60));
+        assert_eq!(ids(&reused), (0..50).collect::<Vec<_>>());
+        assert_eq!(builder.lock().unwrap().current_count(), 50);
+    }
+
     #[test]
     fn can_infect_a_population() {
         let mut pop = Population::new(
@@ -634,6 +2660,29 @@ mod test {
         assert!(pop.infect_one(&pathogen));
     }
 
+    #[test]
+    fn infect_specific_person() {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            100,
+            UniformDistribution::new(20, 60),
+        );
+        let pathogen = Arc::new(Virus.create_pathogen("Test", 0));
+        let id = pop.get_everyone()[42].read().unwrap().id();
+
+        assert!(pop.infect_person(id, &pathogen));
+        assert!(!pop.infect_person(id, &pathogen), "Can't infect someone twice");
+        assert!(!pop.infect_person(usize::MAX, &pathogen));
+
+        assert_eq!(pop.get_infected().len(), 1);
+        assert_eq!(pop.get_infected()[0].read().unwrap().id(), id);
+        for person in pop.get_everyone() {
+            let person = person.read().unwrap();
+            assert_eq!(person.infected(), person.id() == id);
+        }
+    }
+
     #[test]
     fn healthy_population_doesnt_lose_health() {
         let mut pop = Population::new(
@@ -645,7 +2694,7 @@ mod test {
         let healths = pop
             .get_everyone()
             .iter()
-            .map(|p| *p.read().unwrap().health_points().read().unwrap())
+            .map(|p| p.read().unwrap().health_points().read().unwrap().get())
             .collect::<Vec<u32>>();
 
         for _ in 0..1000 {
@@ -655,7 +2704,7 @@ mod test {
         for (pos, person) in pop.get_everyone().iter().enumerate() {
             let person = person.read().unwrap();
             assert_eq!(
-                *person.health_points().read().unwrap(),
+                person.health_points().read().unwrap().get(),
                 *healths.get(pos).unwrap(),
                 "{:?} lost health",
                 &*person
@@ -663,6 +2712,1444 @@ mod test {
         }
     }
 
+    #[test]
+    fn set_age_clamps_health() {
+        let mut person = Person::new(0, Age::new(17, 0, 0), Male, 1.00);
+        let health = person.health_points().read().unwrap().get();
+        person.set_age(Age::new(30, 0, 0));
+        let max_health = Person::max_health(30, &Male, 1.00);
+        assert!(max_health < health);
+        assert_eq!(person.health_points().read().unwrap().get(), max_health);
+        assert_eq!(person.get_age_years(), 30);
+    }
+
+    #[test]
+    fn oldest_people_have_health() {
+        for &age in &[120, 125] {
+            let person = Person::new(0, Age::new(age, 0, 0), Male, 1.00);
+            let health = person.health_points().read().unwrap().get();
+            assert!(health > 0, "A {} year old has no health", age);
+            assert!(person.condition().is_finite());
+        }
+    }
+
+    #[test]
+    fn population_up_to_max_age_has_health() {
+        let pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            1000,
+            UniformDistribution::new(0, MAX_AGE as usize),
+        );
+        let oldest = pop.oldest().unwrap();
+        assert_eq!(read_person(&oldest).get_age_years(), MAX_AGE);
+        for person in pop.get_everyone() {
+            let person = read_person(person);
+            assert!(person.alive(), "{:?} was created dead", person);
+            assert!(person.condition() > 0.0 && person.condition().is_finite());
+            assert!(person.health_fraction() > 0.0);
+        }
+
+        let ancient = Person::new(0, Age::new(300, 0, 0), Male, 1.00);
+        assert_eq!(ancient.get_age_years(), u8::MAX);
+        assert_eq!(ancient.max_health_points(), Person::max_health(MAX_AGE, &Male, 1.00));
+    }
+
+    #[test]
+    fn birthday_detected() {
+        let mut person = Person::new(0, Age::new(17, 0, 0), Male, 1.00);
+        person.set_age((Years(29) + Days(364) + Hours(23) + Minutes(59)).into());
+        assert_eq!(person.birthday(), None);
+
+        person.update(20);
+        assert_eq!(person.birthday(), Some(30));
+
+        person.update(20);
+        assert_eq!(person.birthday(), None);
+    }
+
+    #[test]
+    fn growth_stops_at_max_population() {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            10.0,
+            100,
+            UniformDistribution::new(20, 60),
+        );
+        pop.set_max_population(Some(150));
+        pop.age_a_year();
+        assert_eq!(pop.get_total_population(), 150);
+        assert_eq!(pop.get_everyone().len(), 150);
+
+        pop.age_a_year();
+        assert_eq!(pop.get_total_population(), 150);
+
+        pop.set_max_population(None);
+        pop.update(usize::from(Years(1).into_minutes()) * TICKS_TO_GAME_MIN / 100);
+        assert!(pop.get_total_population() > 150);
+    }
+
+    #[test]
+    fn newborns_get_the_population_settings() {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            10.0,
+            100,
+            UniformDistribution::new(20, 60),
+        );
+        pop.set_damage_interval(Hours(4));
+        pop.set_max_damage_fraction(0.2);
+        pop.set_mortality_model(MortalityModel::CaseFatality(0.5));
+        pop.set_base_immunity(0.3);
+        pop.set_cross_immunity(0.4);
+        pop.update(usize::from(Days(7).into_minutes()) * TICKS_TO_GAME_MIN);
+        assert!(pop.get_total_population() > 100);
+
+        for person in &pop.get_everyone()[100..] {
+            let person = read_person(person);
+            assert_eq!(person.damage_interval(), &Hours(4));
+            assert_eq!(person.max_damage_fraction(), 0.2);
+            assert_eq!(person.mortality_model(), MortalityModel::CaseFatality(0.5));
+            assert_eq!(person.base_immunity(), 0.3);
+            assert_eq!(person.cross_immunity(), 0.4);
+        }
+    }
+
+    #[test]
+    fn aging_matches_ticks_passed() {
+        let mut person = Person::new(0, Age::new(30, 0, 0), Male, 1.00);
+        let start = person.age.lock().unwrap().time_unit().clone();
+
+        // 7 ticks never make up a game minute alone, but the leftovers add up
+        for _ in 0..30 {
+            person.update(7);
+        }
+        assert_eq!(*person.age.lock().unwrap(), &start + Minutes(10));
+        person.update(10);
+        assert_eq!(*person.age.lock().unwrap(), &start + Minutes(11));
+
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            10,
+            UniformDistribution::new(20, 60),
+        );
+        let ages = pop
+            .get_everyone()
+            .iter()
+            .map(|p| read_person(p).age.lock().unwrap().time_unit().clone())
+            .collect::<Vec<_>>();
+        pop.age_a_year();
+        for (person, age) in pop.get_everyone().iter().zip(ages) {
+            assert_eq!(*read_person(person).age.lock().unwrap(), age + Years(1));
+        }
+    }
+
+    /// Runs a single game day for an active, never ending infection and gets the health lost
+    fn health_lost_in_a_day(tick_size: usize) -> u32 {
+        let mut person = Person::new(0, Age::new(17, 0, 0), Male, 1.00);
+        person.set_damage_interval(Hours(1));
+        let mut p = Virus.create_pathogen("Test", 0);
+        p.acquire_symptom(&Undying.get_symptom(), None);
+        p.acquire_symptom(&CustomFatality(99.0).get_symptom(), None);
+        assert!(person.infect(&Arc::new(p)));
+
+        while !person.infections()[0].active_case() {
+            person.update(tick_size);
+        }
+
+        let start = person.health_points().read().unwrap().get();
+        for _ in 0..(20 * 60 * 24 / tick_size) {
+            person.update(tick_size);
+        }
+        let end = person.health_points().read().unwrap().get();
+        start - end
+    }
+
+    #[test]
+    fn daily_health_loss_independent_of_tick_size() {
+        let full_ticks = health_lost_in_a_day(20);
+        let half_ticks = health_lost_in_a_day(10);
+
+        assert!((20..=24).contains(&full_ticks), "Lost {} hp", full_ticks);
+        assert!((20..=24).contains(&half_ticks), "Lost {} hp", half_ticks);
+    }
+
+    #[test]
+    fn reset_population_is_pristine() {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            200,
+            UniformDistribution::new(20, 60),
+        );
+        let ids = pop
+            .get_everyone()
+            .iter()
+            .map(|p| p.read().unwrap().id)
+            .collect::<Vec<usize>>();
+        let mut p = Virus.create_pathogen("Test", 0);
+        p.acquire_symptom(&CustomFatality(99.0).get_symptom(), None);
+        let pathogen = Arc::new(p);
+        for _ in 0..50 {
+            assert!(pop.infect_one(&pathogen));
+        }
+        for _ in 0..2000 {
+            pop.update(20 * 15);
+        }
+        assert!(pop.get_all_ever_infected() > 0 || pop.get_total_population() < 200);
+
+        pop.reset();
+
+        assert!(pop.get_infected().is_empty());
+        assert_eq!(pop.get_total_population(), pop.get_original_population());
+        let after_ids = pop
+            .get_everyone()
+            .iter()
+            .map(|p| p.read().unwrap().id)
+            .collect::<Vec<usize>>();
+        assert_eq!(after_ids, ids);
+        for person in pop.get_everyone() {
+            let person = person.read().unwrap();
+            assert!(person.never_infected(), "{:?} still has an infection", &*person);
+            assert!(!person.recovered());
+            let max_health = Person::max_health(
+                person.get_age_years(),
+                &person.sex,
+                person.pre_existing_condition,
+            );
+            assert_eq!(person.health_points().read().unwrap().get(), max_health);
+        }
+    }
+
+    #[test]
+    fn damage_per_tick_is_capped() {
+        let mut person = Person::new(0, Age::new(17, 0, 0), Male, 1.00);
+        person.set_max_damage_fraction(0.01);
+        let mut p = Virus.create_pathogen("Test", 0);
+        p.acquire_symptom(&Undying.get_symptom(), None);
+        p.acquire_symptom(&CustomFatality(99.0).get_symptom(), None);
+        p.acquire_symptom(&CustomSeverity(90.0).get_symptom(), None);
+        assert!(person.infect(&Arc::new(p)));
+
+        let cap = person.max_damage_per_tick(Person::max_health(17, &Male, 1.00));
+        assert_eq!(cap, 9);
+        let lost_in = |person: &mut Person, delta_time: usize| {
+            let before = person.health_points().read().unwrap().get();
+            person.update(delta_time);
+            before - person.health_points().read().unwrap().get()
+        };
+        let mut hit_cap = false;
+        while !hit_cap {
+            let lost = lost_in(&mut person, 20);
+            assert!(lost <= cap, "Lost {} hp in a single tick", lost);
+            hit_cap = lost == cap;
+        }
+
+        // only the first of the rolls in a longer update hits, which still can't lose more than
+        // the cap
+        let before = person.health_points().read().unwrap().get();
+        person.update_with_aging(30 * 20, true, &mut StepRng::new(0, u64::MAX));
+        let lost = before - person.health_points().read().unwrap().get();
+        assert_eq!(lost, cap, "Lost {} hp in a single roll", lost);
+
+        // an hour long update rolls for damage every minute, so the cap applies to every roll
+        let lost = lost_in(&mut person, 20 * 60);
+        assert!(lost > cap && lost <= 60 * cap, "Lost {} hp in an hour", lost);
+        while person.alive() {
+            let lost = lost_in(&mut person, 20);
+            assert!(lost <= cap, "Lost {} hp in a single tick", lost);
+        }
+    }
+
+    #[test]
+    fn hospitalized_person_is_inactive() {
+        let mut person = Person::new(0, Age::new(17, 0, 0), Male, 1.00);
+        assert_eq!(person.behavioral_activity(), 1.0);
+
+        let mut p = Virus.create_pathogen("Test", 0);
+        p.acquire_symptom(&CustomSeverity(90.0).get_symptom(), None);
+        assert!(person.infect(&Arc::new(p)));
+        let sick_activity = person.behavioral_activity();
+        assert!(sick_activity < 0.1, "Activity was {}", sick_activity);
+
+        *person.condition.lock().unwrap() = Condition::Hospitalized;
+        let hospitalized_activity = person.behavioral_activity();
+        assert!(hospitalized_activity < sick_activity);
+        assert!(hospitalized_activity < 0.01, "Activity was {}", hospitalized_activity);
+    }
+
+    #[test]
+    fn par_for_each_reaches_everyone() {
+        let pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            1000,
+            UniformDistribution::new(0, 120),
+        );
+        pop.par_for_each(|person| person.set_damage_interval(Hours(2)));
+
+        for person in pop.get_everyone() {
+            assert_eq!(person.read().unwrap().damage_interval(), &Hours(2));
+        }
+    }
+
+    #[test]
+    fn exposed_before_infectious() {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            100,
+            UniformDistribution::new(20, 60),
+        );
+        let size = pop.get_everyone().len();
+        let pathogen = Arc::new(Virus.create_pathogen("Test", 0));
+        for _ in 0..5 {
+            assert!(pop.infect_one(&pathogen));
+        }
+
+        let counts = pop.compartments();
+        assert_eq!(counts.exposed, 5);
+        assert_eq!(counts.infectious, 0);
+        assert_eq!(counts.susceptible, size - 5);
+        for person in pop.get_infected() {
+            assert_eq!(person.read().unwrap().compartment(), Compartment::Exposed);
+        }
+
+        while pop.compartments().infectious == 0 {
+            pop.update(20);
+        }
+        let counts = pop.compartments();
+        assert_eq!(counts.exposed + counts.infectious + counts.recovered, 5);
+        assert_eq!(counts.get(Compartment::Susceptible), size - 5);
+    }
+
+    #[test]
+    fn rebuild_infected_fixes_desync() {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            100,
+            UniformDistribution::new(20, 60),
+        );
+        let pathogen = Arc::new(Virus.create_pathogen("Test", 0));
+        for _ in 0..5 {
+            assert!(pop.infect_one(&pathogen));
+        }
+        let mut expected = pop
+            .get_infected()
+            .iter()
+            .map(|p| p.read().unwrap().id)
+            .collect::<Vec<usize>>();
+        expected.sort();
+
+        // duplicate an infected person, drop another, and add someone who isn't infected
+        let duplicate = pop.infected[0].clone();
+        pop.infected.push(duplicate);
+        pop.infected.remove(1);
+        let healthy = pop
+            .people
+            .iter()
+            .find(|p| p.read().unwrap().never_infected())
+            .unwrap()
+            .clone();
+        pop.infected.push(healthy);
+
+        pop.rebuild_infected();
+
+        let mut ids = pop
+            .get_infected()
+            .iter()
+            .map(|p| p.read().unwrap().id)
+            .collect::<Vec<usize>>();
+        ids.sort();
+        assert_eq!(ids, expected);
+    }
+
+    /// Gets the average amount of updates an infection caught from an infector with
+    /// `infector_load` pathogens takes to become an active case
+    fn updates_until_active(infector_load: usize) -> f64 {
+        let mut p = Virus.create_pathogen("Test", 0);
+        p.acquire_symptom(&CustomCatchChance(99.0).get_symptom(), None);
+        p.set_dose_fraction(Some(0.05));
+        let pathogen = Arc::new(p);
+
+        let infector = Person::new(0, Age::new(17, 0, 0), Male, 1.00);
+        infector.infections.lock().unwrap().insert(
+            pathogen.name().clone(),
+            Infection::with_dose(pathogen.clone(), 1.0, infector_load),
+        );
+        assert!(infector.infectious());
+
+        let trials = 20;
+        let mut total = 0;
+        for id in 0..trials {
+            let mut other = Person::new(id + 1, Age::new(17, 0, 0), Male, 1.00);
+            while !infector.interact_with(&mut other) {}
+            while !other.infectious() {
+                other.update(20);
+                total += 1;
+            }
+        }
+        total as f64 / trials as f64
+    }
+
+    #[test]
+    fn high_load_seeds_faster_infections() {
+        let high = updates_until_active(30_000_000);
+        let low = updates_until_active(1_000_001);
+        assert!(high < low, "High load took {} updates, low load took {}", high, low);
+    }
+
+    /// Runs triage on a population where everyone infected needs a hospital and gets the deaths
+    fn triage_deaths(beds: usize) -> usize {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            200,
+            UniformDistribution::new(20, 60),
+        );
+        pop.set_hospital_capacity(Some(beds));
+        pop.set_triage_fatality(0.05);
+        let mut p = Virus.create_pathogen("Test", 0);
+        p.acquire_symptom(&CustomSeverity(90.0).get_symptom(), None);
+        let pathogen = Arc::new(p);
+        for _ in 0..100 {
+            assert!(pop.infect_one(&pathogen));
+        }
+        for person in pop.get_infected() {
+            *person.read().unwrap().condition.lock().unwrap() = Condition::NeedsHospital;
+        }
+
+        let mut deaths = 0;
+        for _ in 0..50 {
+            pop.allocate_hospital_beds();
+            deaths += pop.apply_triage_mortality();
+        }
+        deaths
+    }
+
+    #[test]
+    fn overwhelmed_hospitals_cause_deaths() {
+        let overwhelmed = triage_deaths(5);
+        let adequate = triage_deaths(100);
+
+        assert_eq!(adequate, 0);
+        assert!(overwhelmed > 50, "Only {} died without beds", overwhelmed);
+    }
+
+    #[test]
+    fn uniform_demographics() {
+        let pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            1000,
+            UniformDistribution::new(20, 40),
+        );
+        let average = pop.average_age();
+        assert!((28.0..=32.0).contains(&average), "Average age was {}", average);
+
+        let histogram = pop.age_histogram(10);
+        assert_eq!(histogram.len(), 13);
+        assert_eq!(histogram.iter().sum::<usize>(), pop.get_everyone().len());
+        for (bin, count) in histogram.iter().enumerate() {
+            if !(2..=4).contains(&bin) {
+                assert_eq!(*count, 0, "{} people in the bin starting at {}", count, bin * 10);
+            }
+        }
+    }
+
+    #[test]
+    fn young_pyramids_are_younger() {
+        let young = PyramidDistribution::young();
+        let aging = PyramidDistribution::aging();
+        for pyramid in [&young, &aging] {
+            let total: f64 = (0..=MAX_AGE as usize)
+                .map(|age| pyramid.get_percent_of_pop(age))
+                .sum();
+            assert!((total - 1.0).abs() < 1e-9, "{} adds up to {}", pyramid.name(), total);
+        }
+
+        let young = Population::new(&PersonBuilder::new(), 0.0, 2000, young);
+        let aging = Population::new(&PersonBuilder::new(), 0.0, 2000, aging);
+        assert!(
+            young.average_age() + 10.0 < aging.average_age(),
+            "Young average was {}, aging average was {}",
+            young.average_age(),
+            aging.average_age()
+        );
+    }
+
+    #[test]
+    fn cleanup_is_a_separate_phase() {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            100,
+            UniformDistribution::new(20, 60),
+        );
+        let person = pop.find_by_id(5).unwrap();
+        *person.read().unwrap().health_points().write().unwrap() = HealthPoints::new(0);
+
+        pop.update_without_cleanup(20);
+        assert!(pop.get_everyone().iter().any(|p| Arc::ptr_eq(p, &person)));
+        assert_eq!(pop.get_total_population(), 100);
+
+        pop.cleanup();
+        assert!(!pop.get_everyone().iter().any(|p| Arc::ptr_eq(p, &person)));
+        assert_eq!(pop.get_total_population(), 99);
+    }
+
+    #[test]
+    fn interaction_outcomes() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let mut infector = Person::new(0, Age::new(30, 0, 0), Male, 1.0);
+        let mut other = Person::new(1, Age::new(30, 0, 0), Male, 1.0);
+        let outcome = infector.interaction_outcome(&mut other, None, &mut rng);
+        assert!(matches!(outcome, InteractionOutcome::NotInfectious));
+
+        // the default catch chance is far too low to ever spread
+        let unspreadable = Arc::new(Pathogen::default());
+        assert!(infector.infect_with_dose(&unspreadable, 300_000_000));
+        let outcome = infector.interaction_outcome(&mut other, None, &mut rng);
+        assert!(matches!(outcome, InteractionOutcome::NoTransmission));
+        assert!(!other.infected());
+
+        let mut p = Virus.create_pathogen("Test", 0);
+        p.acquire_symptom(&CustomCatchChance(99.0).get_symptom(), None);
+        let pathogen = Arc::new(p);
+        assert!(infector.infect_with_dose(&pathogen, 30_000_000));
+        let mut tries = 0;
+        let strain = loop {
+            match infector.interaction_outcome(&mut other, None, &mut rng) {
+                InteractionOutcome::Infected { strain } => break strain,
+                outcome => assert!(!outcome.infected()),
+            }
+            tries += 1;
+            assert!(tries < 100, "The pathogen never spread");
+        };
+        assert_eq!(strain.name(), pathogen.name());
+        assert!(other.infected());
+
+        // someone who already has every infection the infector has can't catch anything
+        let outcome = infector.interaction_outcome(&mut other, None, &mut rng);
+        assert!(matches!(outcome, InteractionOutcome::NoTransmission));
+        assert!(other.infect_with_dose(&unspreadable, 300_000_000));
+        let outcome = infector.interaction_outcome(&mut other, None, &mut rng);
+        assert!(matches!(outcome, InteractionOutcome::NotInfectious));
+    }
+
+    #[test]
+    fn sequelae_lower_max_health_for_good() {
+        let mut person = Person::new(0, Age::new(30, 0, 0), Male, 1.0);
+        person.set_mortality_model(MortalityModel::CaseFatality(0.0));
+        let mut p = Virus.create_pathogen("Test", 0);
+        p.set_sequelae(1.0, 0.25);
+        let pathogen = Arc::new(p);
+        let max_health = person.max_health_points();
+
+        assert!(person.infect_with_dose(&pathogen, 30_000_000));
+        while !person.recovered() {
+            person.update(20 * 60 * 6);
+        }
+        let scarred = person.max_health_points();
+        assert_eq!(scarred, (max_health as f64 * 0.75) as u32);
+        assert!(person.health_points().read().unwrap().get() <= scarred);
+
+        person.update(20 * 60 * 24 * 30);
+        person.remove_immunity();
+        assert_eq!(person.max_health_points(), scarred);
+    }
+
+    #[test]
+    fn health_points_saturate() {
+        let mut hp = HealthPoints::new(10);
+        assert_eq!(hp.saturating_damage(4), 4);
+        assert_eq!(hp, 6);
+        assert_eq!(hp.saturating_damage(100), 6);
+        assert_eq!(hp, 0);
+        assert_eq!(hp.saturating_damage(1), 0);
+
+        hp.heal(50, 30);
+        assert_eq!(hp, 30);
+        hp.heal(u32::MAX, 40);
+        assert_eq!(hp, 40);
+        hp.cap(25);
+        assert_eq!(hp, 25);
+        hp.heal(10, 20);
+        assert_eq!(hp, 25);
+    }
+
+    #[test]
+    fn people_heal_up_to_their_max_health() {
+        let mut person = Person::new(0, Age::new(30, 0, 0), Male, 1.0);
+        let max_health = person.max_health_points();
+        person.health_points().write().unwrap().saturating_damage(100);
+        person.heal(40);
+        assert_eq!(*person.health_points().read().unwrap(), max_health - 60);
+        person.heal(u32::MAX);
+        assert_eq!(*person.health_points().read().unwrap(), max_health);
+
+        *person.health_points().write().unwrap() = HealthPoints::new(0);
+        person.heal(10);
+        assert!(person.dead());
+    }
+
+    #[test]
+    fn total_health_lost_matches_the_drop() {
+        let mut person = Person::new(0, Age::new(30, 0, 0), Male, 1.0);
+        let mut p = Virus.create_pathogen("Test", 0);
+        p.acquire_symptom(&CustomFatality(99.0).get_symptom(), None);
+        let pathogen = Arc::new(p);
+        let start = person.health_points().read().unwrap().get();
+
+        assert!(person.infect_with_dose(&pathogen, 30_000_000));
+        while person.infected() && person.alive() {
+            person.update(20 * 60);
+        }
+        let end = person.health_points().read().unwrap().get();
+        assert!(end < start);
+        assert_eq!(person.total_health_lost(), u64::from(start - end));
+
+        person.reset();
+        assert_eq!(person.total_health_lost(), 0);
+    }
+
+    #[test]
+    fn health_thresholds_fire_once() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let log = events.clone();
+        let watch = HealthWatch::new(
+            &[0.5, 0.25],
+            Box::new(move |event| log.lock().unwrap().push(event)),
+        );
+        let mut person = Person::new(0, Age::new(30, 0, 0), Male, 1.0);
+        person.set_mortality_model(MortalityModel::CaseFatality(1.0));
+        person.watch_health(Some(Arc::new(watch)));
+
+        let pathogen = Arc::new(Virus.create_pathogen("Test", 0));
+        assert!(person.infect_with_dose(&pathogen, 30_000_000));
+        while person.alive() {
+            person.update(20 * 60);
+        }
+        for _ in 0..10 {
+            person.update(20 * 60);
+        }
+
+        let events = events.lock().unwrap();
+        let critical = events.iter().filter(|e| e.threshold == 0.25).collect::<Vec<_>>();
+        assert_eq!(critical.len(), 1, "Events were {:?}", events);
+        assert_eq!(critical[0].person, 0);
+        assert!((critical[0].health_points as f64) < person.max_health_points() as f64 * 0.25);
+    }
+
+    #[test]
+    fn swapped_strain_sets_severity() {
+        let mut person = Person::new(0, Age::new(30, 0, 0), Male, 1.0);
+        let mild = Arc::new(Virus.create_pathogen("Test", 0));
+        assert!(person.infect_with_dose(&mild, 30_000_000));
+        person.update(20 * 60);
+        let activity = person.behavioral_activity();
+
+        let mut severe = (*mild).clone();
+        severe.acquire_symptom(&CustomSeverity(90.0).get_symptom(), None);
+        let severe = Arc::new(severe);
+        let (age, load) = {
+            let mut infections = person.infections.lock().unwrap();
+            let infection = infections.get_mut(mild.name()).unwrap();
+            let before = (infection.infection_age().clone(), infection.pathogen_load());
+            infection.set_pathogen(severe.clone());
+            before
+        };
+
+        person.update(20 * 60);
+        let infections = person.infections.lock().unwrap();
+        let infection = &infections[mild.name()];
+        assert!(Arc::ptr_eq(infection.get_pathogen(), &severe));
+        assert_eq!(infection.pathogen_load(), load);
+        assert_eq!(
+            infection.infection_age().time_unit(),
+            &(age.time_unit() + Minutes(60))
+        );
+        drop(infections);
+        assert_eq!(person.severity(), severe.severity());
+        assert!(person.behavioral_activity() < activity);
+    }
+
+    #[test]
+    fn removal_callbacks_get_every_death() {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            100,
+            UniformDistribution::new(20, 60),
+        );
+        let removed = Arc::new(Mutex::new(Vec::new()));
+        let log = removed.clone();
+        pop.on_remove(Box::new(move |id| log.lock().unwrap().push(id)));
+
+        for id in [5, 17, 42] {
+            let person = pop.find_by_id(id).unwrap();
+            *read_person(&person).health_points().write().unwrap() = HealthPoints::new(0);
+        }
+        pop.update(20);
+        removed.lock().unwrap().sort_unstable();
+        assert_eq!(*removed.lock().unwrap(), vec![5, 17, 42]);
+
+        // the dead are only removed once
+        pop.update(20);
+        assert_eq!(removed.lock().unwrap().len(), 3);
+        assert_eq!(pop.get_total_population(), 97);
+    }
+
+    #[test]
+    fn birthday_callbacks_get_every_birthday() {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            100,
+            UniformDistribution::new(20, 60),
+        );
+        let birthdays = Arc::new(Mutex::new(Vec::new()));
+        let log = birthdays.clone();
+        pop.on_birthday(Box::new(move |id, years| log.lock().unwrap().push((id, years))));
+
+        pop.par_for_each(|person| person.set_age(Age::new(40, 0, 0)));
+        for id in [3, 8] {
+            let person = pop.find_by_id(id).unwrap();
+            let almost = Years(29) + Days(364) + Hours(23) + Minutes(59);
+            person.write().unwrap().set_age(almost.into());
+        }
+        pop.update(20);
+        birthdays.lock().unwrap().sort_unstable();
+        assert_eq!(*birthdays.lock().unwrap(), vec![(3, 30), (8, 30)]);
+
+        pop.update(20);
+        assert_eq!(birthdays.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn poisoned_people_keep_updating() {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            100,
+            UniformDistribution::new(20, 60),
+        );
+        let pathogen = Arc::new(Virus.default());
+        let person = pop.find_by_id(5).unwrap();
+        assert!(pop.infect_person(5, &pathogen));
+
+        let poisoned = person.clone();
+        let failed_update = std::thread::spawn(move || {
+            let _guard = poisoned.write().unwrap();
+            panic!("Update failed while the person was locked");
+        });
+        assert!(failed_update.join().is_err());
+        assert!(person.is_poisoned());
+
+        let age = read_person(&person).get_age_years();
+        let pop = Arc::new(Mutex::new(pop));
+        let mut controller = InteractionController::new(&pop);
+        for _ in 0..500 {
+            pop.lock().unwrap().update(438 * 100);
+            controller.run();
+        }
+
+        let pop = pop.lock().unwrap();
+        let person = read_person(&person);
+        assert!(person.get_age_years() > age);
+        assert!(!person.infected());
+        assert_eq!(pop.compartments().infectious + pop.compartments().exposed, 0);
+    }
+
+    #[test]
+    fn statistics_are_consistent() {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            300,
+            UniformDistribution::new(20, 60),
+        );
+        let mut p = Virus.create_pathogen("Test", 0);
+        p.acquire_symptom(&CustomCatchChance(99.0).get_symptom(), None);
+        p.acquire_symptom(&CustomFatality(20.0).get_symptom(), None);
+        let pathogen = Arc::new(p);
+        // several cases, so the outbreak doesn't die out before it spreads
+        for id in 0..5 {
+            assert!(pop.infect_person(id, &pathogen));
+        }
+
+        let pop = Arc::new(Mutex::new(pop));
+        let mut controller = InteractionController::new(&pop);
+        controller.set_seed(2165);
+        for _ in 0..300 {
+            {
+                let mut pop = pop.lock().unwrap();
+                pop.update(20 * 60);
+
+                let stats = pop.statistics();
+                assert_eq!(stats.total(), stats.original);
+                assert_eq!(stats.original, pop.get_original_population());
+                assert_eq!(stats.living(), pop.get_total_population());
+                assert_eq!(stats.ever_infected, stats.total() - stats.susceptible);
+                let compartments = pop.compartments();
+                assert_eq!(
+                    (stats.exposed, stats.infectious, stats.dead),
+                    (compartments.exposed, compartments.infectious, compartments.dead)
+                );
+            }
+            controller.run();
+        }
+        assert!(pop.lock().unwrap().statistics().ever_infected > 5);
+    }
+
+    #[test]
+    fn vaccinated_cohort_is_infected_less() {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            400,
+            UniformDistribution::new(20, 60),
+        );
+        let mut p = Virus.create_pathogen("Test", 0);
+        p.acquire_symptom(&CustomCatchChance(99.0).get_symptom(), None);
+        let pathogen = Arc::new(p);
+
+        let (vaccinated, control): (Vec<usize>, Vec<usize>) =
+            (0..400).partition(|id| id % 2 == 0);
+        assert_eq!(pop.assign_cohort(&vaccinated, "vaccinated"), 200);
+        assert_eq!(pop.assign_cohort(&control, "control"), 200);
+        // vaccinated people were immunized by recovering from an earlier exposure
+        for id in &vaccinated {
+            let person = pop.find_by_id(*id).unwrap();
+            let mut person = person.write().unwrap();
+            assert!(person.infect(&pathogen));
+            assert!(person.cure(pathogen.name()));
+        }
+        let immunized = pop.cohort_outcomes("vaccinated").ever_infected;
+        assert!(pop.infect_person(1, &pathogen));
+
+        let pop = Arc::new(Mutex::new(pop));
+        let mut controller = InteractionController::new(&pop);
+        for _ in 0..500 {
+            pop.lock().unwrap().update(20 * 60);
+            controller.run();
+        }
+
+        let pop = pop.lock().unwrap();
+        let vaccinated = pop.cohort_outcomes("vaccinated");
+        let control = pop.cohort_outcomes("control");
+        assert_eq!(vaccinated.original, 200);
+        assert_eq!(control.original, 200);
+        let vaccinated_rate = (vaccinated.ever_infected - immunized) as f64 / 200.0;
+        let control_rate = control.ever_infected as f64 / 200.0;
+        assert!(
+            vaccinated_rate < control_rate,
+            "Vaccinated attack rate was {}, control attack rate was {}",
+            vaccinated_rate,
+            control_rate
+        );
+        assert_eq!(pop.cohort_outcomes("nobody"), PopulationStats::default());
+    }
+
+    fn age_in_minutes(person: &Arc<RwLock<Person>>) -> usize {
+        usize::from(read_person(person).age.lock().unwrap().0.as_minutes())
+    }
+
+    /// Runs a seeded outbreak for four days on a copy of `pop`, with or without batched aging,
+    /// and gets its transmissions, final statistics, and everyone's age in minutes
+    fn seeded_four_days(
+        pop: &Population,
+        batched: bool,
+    ) -> (Vec<Transmission>, PopulationStats, Vec<usize>) {
+        let mut pop = pop.deep_copy(&PersonBuilder::new());
+        if batched {
+            pop.set_aging_interval(Some(Days(1)));
+        }
+        let pop = Arc::new(Mutex::new(pop));
+        let tree = Arc::new(TransmissionTree::new());
+        let mut controller = InteractionController::new(&pop);
+        controller.record_transmissions(&tree);
+        controller.set_seed(7);
+        for _ in 0..4 * 24 {
+            pop.lock().unwrap().update(20 * 60);
+            controller.run();
+        }
+
+        let pop = pop.lock().unwrap();
+        let ages = pop.get_everyone().iter().map(age_in_minutes).collect();
+        (tree.transmissions(), pop.statistics(), ages)
+    }
+
+    #[test]
+    fn batched_aging_matches_full_updates() {
+        // everyone keeps the same max health while aging, and infections last exactly two days,
+        // so the seeded controller decides every outcome
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            300,
+            UniformDistribution::new(10, 18),
+        );
+        pop.set_mortality_model(MortalityModel::CaseFatality(0.0));
+        let mut p = Pathogen::new(
+            "Test".to_string(),
+            0,
+            0.0,
+            2 * 24 * 60,
+            0,
+            Graph::new(),
+            HashSet::new(),
+        );
+        p.acquire_symptom(&CustomCatchChance(10.0).get_symptom(), None);
+        let pathogen = Arc::new(p);
+        for id in 0..5 {
+            assert!(pop.infect_person(id, &pathogen));
+        }
+
+        let (full, full_stats, full_ages) = seeded_four_days(&pop, false);
+        let (batched, batched_stats, batched_ages) = seeded_four_days(&pop, true);
+        assert!(full.len() > 5, "Only {} transmissions", full.len());
+        assert_eq!(full, batched);
+        assert_eq!(full_stats, batched_stats);
+        assert!(full_stats.recovered > 0);
+        assert_eq!(full_ages, batched_ages);
+        let before = age_in_minutes(&pop.get_everyone()[0]);
+        assert_eq!(full_ages[0], before + 4 * 24 * 60);
+    }
+
+    #[test]
+    fn seeded_updates_are_reproducible() {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            200,
+            UniformDistribution::new(20, 60),
+        );
+        let mut p = Pathogen::new(
+            "Test".to_string(),
+            200,
+            0.1,
+            2 * 24 * 60,
+            24 * 60,
+            Graph::new(),
+            HashSet::new(),
+        );
+        p.acquire_symptom(&CustomFatality(20.0).get_symptom(), None);
+        let pathogen = Arc::new(p);
+        for id in 0..100 {
+            assert!(pop.infect_person(id, &pathogen));
+        }
+
+        let seeded_two_days = || {
+            let mut pop = pop.deep_copy(&PersonBuilder::new());
+            pop.set_seed(2178);
+            assert!(pop.deterministic());
+            for _ in 0..2 * 24 {
+                pop.update(20 * 60);
+            }
+            let health = pop
+                .get_everyone()
+                .iter()
+                .map(|p| p.read().unwrap().health_fraction())
+                .collect::<Vec<_>>();
+            (pop.statistics(), health)
+        };
+        let (stats, health) = seeded_two_days();
+        assert!(stats.dead > 0);
+        assert_eq!(seeded_two_days(), (stats, health));
+    }
+
+    #[test]
+    fn find_people() {
+        let pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            100,
+            UniformDistribution::new(20, 60),
+        );
+        pop.par_for_each(|person| person.set_age(Age::new(30, 0, 0)));
+        pop.find_by_id(7)
+            .unwrap()
+            .write()
+            .unwrap()
+            .set_age(Age::new(100, 0, 0));
+        pop.find_by_id(31)
+            .unwrap()
+            .write()
+            .unwrap()
+            .set_age(Age::new(2, 0, 0));
+
+        assert_eq!(pop.find_by_id(42).unwrap().read().unwrap().id(), 42);
+        assert!(pop.find_by_id(100).is_none());
+        assert_eq!(pop.oldest().unwrap().read().unwrap().id(), 7);
+        assert_eq!(pop.youngest().unwrap().read().unwrap().id(), 31);
+    }
+
+    #[test]
+    fn people_in_age_range_are_within_it() {
+        let pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            1000,
+            UniformDistribution::new(0, 100),
+        );
+        let seniors = pop.people_in_age_range(65..MAX_AGE as u16 + 1);
+        assert!(!seniors.is_empty());
+        for person in &seniors {
+            assert!(read_person(person).get_age_years() >= 65);
+        }
+        let expected = pop
+            .get_everyone()
+            .iter()
+            .filter(|p| read_person(p).get_age_years() >= 65)
+            .count();
+        assert_eq!(seniors.len(), expected);
+
+        let adults = pop.people_in_age_range(20..40);
+        assert!(adults
+            .iter()
+            .all(|p| (20..40).contains(&read_person(p).get_age_years())));
+        assert!(pop.people_in_age_range(30..30).is_empty());
+    }
+
+    #[test]
+    fn exposures_can_fizzle() {
+        let mut pathogen = Virus.create_pathogen("Test", 0);
+        pathogen.set_establishment_chance(0.1);
+        let pathogen = Arc::new(pathogen);
+
+        let mut established = 0;
+        for id in 0..500 {
+            let mut person = Person::new(id, Age::new(30, 0, 0), Male, 1.0);
+            if person.infect(&pathogen) {
+                established += 1;
+            } else {
+                assert!(person.never_infected());
+                assert_eq!(person.compartment(), Compartment::Susceptible);
+            }
+        }
+        assert!(established > 0);
+        assert!(established < 150, "{} of 500 exposures took hold", established);
+    }
+
+    #[test]
+    fn person_cannot_infect_themselves() {
+        let mut pathogen = Virus.create_pathogen("Test", 0);
+        pathogen.acquire_symptom(&CustomCatchChance(99.0).get_symptom(), None);
+        let pathogen = Arc::new(pathogen);
+
+        let age = Age::new(30, 0, 0);
+        let mut infector = Person::new(0, age.clone(), Male, 1.0);
+        assert!(infector.infect_with_dose(&pathogen, 30_000_000));
+        let mut same_id = Person::new(0, age.clone(), Male, 1.0);
+        let mut other = Person::new(1, age, Male, 1.0);
+
+        for _ in 0..100 {
+            assert!(!infector.interact_with(&mut same_id));
+        }
+        assert!(same_id.never_infected());
+        assert!((0..100).any(|_| infector.interact_with(&mut other)));
+    }
+
+    #[test]
+    fn deep_copy_evolves_independently() {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            200,
+            UniformDistribution::new(20, 60),
+        );
+        let pathogen = Arc::new(Virus.default());
+        for _ in 0..20 {
+            assert!(pop.infect_one(&pathogen));
+        }
+        pop.update(20 * 60);
+
+        let mut copy = pop.deep_copy(&PersonBuilder::new());
+        assert_eq!(copy.get_everyone().len(), pop.get_everyone().len());
+        assert_eq!(copy.get_infected().len(), 20);
+        assert_eq!(copy.get_all_ever_infected(), pop.get_all_ever_infected());
+        for (original, copied) in pop.get_everyone().iter().zip(copy.get_everyone()) {
+            let original = original.read().unwrap();
+            let copied = copied.read().unwrap();
+            assert_eq!(original.id(), copied.id());
+            assert_eq!(original.get_age_years(), copied.get_age_years());
+            assert_eq!(original.infected(), copied.infected());
+            assert_eq!(original.condition(), copied.condition());
+        }
+
+        copy.reset();
+        assert_eq!(copy.get_all_ever_infected(), 0);
+        assert_eq!(pop.get_all_ever_infected(), 20);
+        assert_eq!(pop.get_infected().len(), 20);
+    }
+
+    #[test]
+    fn deep_copy_keeps_health_modifiers() {
+        let person = Person::new(0, Age::new(30, 0, 0), Male, 1.0);
+        person.modifiers.lock().unwrap().push(Box::new(Female));
+        let copy = PersonBuilder::new().lock().unwrap().copy_person(&person);
+
+        let factors = |person: &Person| {
+            person
+                .modifiers
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|m| m.get_health_modification_factor())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(factors(&copy), vec![1.0]);
+        assert_eq!(factors(&copy), factors(&person));
+    }
+
+    #[test]
+    fn simulated_final_size_matches_theory() {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            1000,
+            UniformDistribution::new(20, 60),
+        );
+        pop.set_mortality_model(MortalityModel::CaseFatality(0.0));
+        let mut p = Pathogen::new(
+            "Test".to_string(),
+            0,
+            0.0,
+            2 * 24 * 60,
+            0,
+            Graph::new(),
+            HashSet::new(),
+        );
+        p.acquire_symptom(&CustomCatchChance(0.1).get_symptom(), None);
+        let pathogen = Arc::new(p);
+        let r0 = pop.estimated_r0(&pathogen, 1.0);
+        assert!(r0 > 1.5 && r0 < 2.5, "R0 was {}", r0);
+        let expected = pop.expected_final_size(&pathogen);
+        assert!((expected - (1.0 - (-r0 * expected).exp())).abs() < 1e-9);
+
+        for person in pop.get_everyone().iter().take(10) {
+            assert!(person.write().unwrap().infect_with_dose(&pathogen, 30_000_000));
+        }
+        pop.rebuild_infected();
+        let pop = Arc::new(Mutex::new(pop));
+        let mut controller = InteractionController::new(&pop);
+        controller.set_seed(2186);
+        controller.set_time_step(Hours(1));
+        while !pop.lock().unwrap().get_infected().is_empty() {
+            controller.run();
+            pop.lock().unwrap().update(20 * 60);
+        }
+
+        let pop = pop.lock().unwrap();
+        let simulated = pop.get_all_ever_infected() as f64 / pop.get_total_population() as f64;
+        assert!(
+            (simulated - expected).abs() < 0.1,
+            "Simulated {}, expected {}",
+            simulated,
+            expected
+        );
+    }
+
+    #[test]
+    fn attack_rate_covers_every_condition() {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            1000,
+            UniformDistribution::new(20, 60),
+        );
+        let pathogen = Arc::new(Virus.default());
+        for _ in 0..300 {
+            assert!(pop.infect_one(&pathogen));
+        }
+
+        let rates = pop.attack_rate_by_condition(5);
+        assert_eq!(
+            rates.iter().map(|(bound, _)| *bound).collect::<Vec<_>>(),
+            vec![0.0, 0.2, 0.4, 0.6, 0.8]
+        );
+        for (bound, rate) in &rates {
+            assert!((0.0..=1.0).contains(rate), "Rate at {} was {}", bound, rate);
+        }
+        // nobody has a pre-existing condition below 0.3, and most are at 1.0
+        assert_eq!(rates[0].1, 0.0);
+        assert!(rates[4].1 > 0.0);
+    }
+
+    /// Infects and cures 500 people, then gets how many of them resist being infected again
+    fn resisted_reinfections(severity: Option<f64>) -> usize {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            500,
+            UniformDistribution::new(20, 60),
+        );
+        pop.set_base_immunity(0.2);
+        let mut pathogen = Virus.create_pathogen("Test", 0);
+        if let Some(severity) = severity {
+            pathogen.acquire_symptom(&CustomSeverity(severity).get_symptom(), None);
+        }
+        let pathogen = Arc::new(pathogen);
+
+        pop.get_everyone()
+            .iter()
+            .filter(|person| {
+                let mut person = person.write().unwrap();
+                assert!(person.infect(&pathogen));
+                assert!(person.cure(pathogen.name()));
+                !person.infect(&pathogen)
+            })
+            .count()
+    }
+
+    #[test]
+    fn severe_infections_leave_stronger_immunity() {
+        let mild = resisted_reinfections(None);
+        let severe = resisted_reinfections(Some(90.0));
+        assert!(mild < 500, "Every mild case resisted reinfection");
+        assert!(
+            mild < severe,
+            "Mild cases resisted {} reinfections, severe cases resisted {}",
+            mild,
+            severe
+        );
+    }
+
+    #[test]
+    fn immunity_weakens_against_divergent_strains() {
+        let ids = (0..11).collect::<Vec<usize>>();
+        let strain = |acquired: &[usize]| {
+            let mut builder = SymptomMapBuilder::new();
+            for _ in &ids {
+                builder.push(CustomCatchChance(1.0).get_symptom());
+            }
+            Arc::new(Pathogen::new(
+                "Test".to_string(),
+                0,
+                0.0,
+                2 * 24 * 60,
+                0,
+                builder,
+                acquired.iter().copied().collect(),
+            ))
+        };
+        let original = strain(&ids[..10]);
+        let near_identical = strain(&ids);
+        let divergent = strain(&ids[10..]);
+
+        let mut rng = StdRng::seed_from_u64(2190);
+        let mut resisted_near_identical = 0;
+        for id in 0..100 {
+            let recovered = || {
+                let mut person = Person::new(id, Age::new(30, 0, 0), Male, 1.0);
+                person.set_cross_immunity(0.0);
+                assert!(person.infect(&original));
+                assert!(person.cure(original.name()));
+                person
+            };
+            let mut person = recovered();
+            assert_eq!(person.immunity_to(&original), 1.0);
+            assert_eq!(person.immunity_to(&divergent), 0.0);
+            assert!(person.infect_with_rng(&divergent, 1_000_000, &mut rng));
+
+            let mut person = recovered();
+            if !person.infect_with_rng(&near_identical, 1_000_000, &mut rng) {
+                resisted_near_identical += 1;
+            }
+        }
+        assert!(
+            resisted_near_identical > 80,
+            "Only resisted {} near identical strains",
+            resisted_near_identical
+        );
+    }
+
+    #[test]
+    fn large_population_is_pre_sized() {
+        let pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            100_000,
+            UniformDistribution::new(0, 60),
+        );
+        assert_eq!(pop.get_everyone().len(), 100_000);
+        assert!(pop.people.capacity() >= 100_000);
+
+        let pop = Population::with_capacity(
+            &PersonBuilder::new(),
+            0.0,
+            100,
+            UniformDistribution::new(0, 60),
+            1000,
+        );
+        assert_eq!(pop.get_everyone().len(), 100);
+        assert!(pop.people.capacity() >= 1000);
+    }
+
+    /// Gets whether everyone infected is hospitalized after a few bed allocations, when everyone
+    /// infected starts out needing a hospital
+    fn hospitalized_after_allocation(beds: Option<usize>) -> Vec<bool> {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            100,
+            UniformDistribution::new(20, 60),
+        );
+        pop.set_hospital_capacity(beds);
+        let mut p = Virus.create_pathogen("Test", 0);
+        p.acquire_symptom(&CustomSeverity(90.0).get_symptom(), None);
+        let pathogen = Arc::new(p);
+        for _ in 0..20 {
+            assert!(pop.infect_one(&pathogen));
+        }
+        for person in pop.get_infected() {
+            *person.read().unwrap().condition.lock().unwrap() = Condition::NeedsHospital;
+        }
+
+        for _ in 0..20 {
+            pop.allocate_hospital_beds();
+        }
+        pop.get_infected()
+            .iter()
+            .map(|p| *p.read().unwrap().condition.lock().unwrap() == Condition::Hospitalized)
+            .collect()
+    }
+
+    #[test]
+    fn severe_cases_seek_hospital_beds() {
+        assert!(hospitalized_after_allocation(Some(20)).iter().all(|h| *h));
+        assert!(!hospitalized_after_allocation(Some(0)).iter().any(|h| *h));
+        assert!(!hospitalized_after_allocation(None).iter().any(|h| *h));
+    }
+
+    #[test]
+    fn case_fatality_rate_is_exact() {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            2000,
+            UniformDistribution::new(20, 59),
+        );
+        pop.set_mortality_model(MortalityModel::CaseFatality(0.1));
+        let pathogen = Arc::new(Virus.create_pathogen("Test", 0));
+        let ids = pop
+            .get_everyone()
+            .iter()
+            .map(|p| p.read().unwrap().id())
+            .collect::<Vec<usize>>();
+        for id in ids {
+            assert!(pop.infect_person(id, &pathogen));
+        }
+
+        while !pop.get_infected().is_empty() {
+            pop.update(20 * 60);
+        }
+
+        let counts = pop.compartments();
+        assert_eq!(counts.dead + counts.recovered, 2000);
+        assert!(
+            (140..=260).contains(&counts.dead),
+            "{} of 2000 died with a 10% case fatality rate",
+            counts.dead
+        );
+    }
+
+    #[test]
+    fn mutations_create_circulating_strains() {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            300,
+            UniformDistribution::new(20, 60),
+        );
+        let mut p = Virus.create_pathogen("Test", 0);
+        p.acquire_symptom(&CustomCatchChance(99.0).get_symptom(), None);
+        p.set_mutation_weight(0, 1, 0.5).unwrap();
+        p.set_mutation_weight(1, 2, 0.5).unwrap();
+        let pathogen = Arc::new(p);
+        assert!(pop.infect_one(&pathogen));
+        assert_eq!(pop.circulating_strains().len(), 1);
+
+        let pop = Arc::new(Mutex::new(pop));
+        let mut controller = InteractionController::new(&pop);
+        for _ in 0..500 {
+            if pop.lock().unwrap().get_all_ever_infected() >= 100 {
+                break;
+            }
+            pop.lock().unwrap().update(20);
+            controller.run();
+        }
+
+        let pop = pop.lock().unwrap();
+        let strains = pop.circulating_strains();
+        assert!(strains.len() > 1, "Only found {:?}", strains);
+        let active = pop
+            .get_infected()
+            .iter()
+            .filter(|p| p.read().unwrap().infected())
+            .count();
+        assert_eq!(strains.values().sum::<usize>(), active);
+    }
+
+    #[test]
+    fn escalates_to_needs_hospital() {
+        let mut person = Person::new(0, Age::new(17, 0, 0), Male, 1.00);
+        assert_eq!(person.condition_state(), Condition::Normal);
+        let mut p = Virus.create_pathogen("Test", 0);
+        p.acquire_symptom(&Undying.get_symptom(), None);
+        p.acquire_symptom(&CustomFatality(99.0).get_symptom(), None);
+        assert!(person.infect(&Arc::new(p)));
+
+        let max_health = Person::max_health(17, &Male, 1.00);
+        while person.health_points().read().unwrap().get() >= max_health / 4 {
+            person.update(20 * 60);
+        }
+        assert_eq!(person.condition_state(), Condition::NeedsHospital);
+    }
+
+    #[test]
+    fn health_fraction_is_relative_to_max_health() {
+        let person = Person::new(0, Age::new(45, 0, 0), Male, 0.8);
+        let max_health = person.max_health_points();
+        assert_eq!(max_health, Person::max_health(45, &Male, 0.8));
+        assert_eq!(person.health_fraction(), 1.0);
+
+        *person.health_points().write().unwrap() = HealthPoints::new(max_health / 2);
+        assert!((person.health_fraction() - 0.5).abs() < 0.01);
+
+        *person.health_points().write().unwrap() = HealthPoints::new(0);
+        assert_eq!(person.health_fraction(), 0.0);
+    }
+
+    /// Infects a person with a lethal pathogen that needs a severity above 0.5 to need hospital
+    /// care, and gets their condition once they drop below a quarter of their health
+    fn condition_when_weakened(severity: Option<f64>) -> Condition {
+        let mut person = Person::new(0, Age::new(17, 0, 0), Male, 1.00);
+        let mut p = Virus.create_pathogen("Test", 0);
+        p.acquire_symptom(&Undying.get_symptom(), None);
+        p.acquire_symptom(&CustomFatality(99.0).get_symptom(), None);
+        if let Some(severity) = severity {
+            p.acquire_symptom(&CustomSeverity(severity).get_symptom(), None);
+        }
+        p.set_hospitalization_threshold(0.5);
+        assert!(person.infect(&Arc::new(p)));
+
+        let max_health = Person::max_health(17, &Male, 1.00);
+        while person.health_points().read().unwrap().get() >= max_health / 4 {
+            person.update(20 * 60);
+        }
+        person.condition_state()
+    }
+
+    #[test]
+    fn mild_pathogens_never_need_hospital() {
+        assert_eq!(condition_when_weakened(None), Condition::Normal);
+        assert_eq!(condition_when_weakened(Some(90.0)), Condition::NeedsHospital);
+    }
+
     #[test]
     fn can_kill_a_person() {
         let mut person_a = Person::new(0, Age::new(17, 0, 0), Male, 1.00);
@@ -1,31 +1,635 @@
+use std::collections::HashMap;
 use std::io::{stdout, Write};
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::SeqCst;
 use std::sync::{Arc, Mutex, RwLockReadGuard, RwLockWriteGuard, TryLockError};
 
-use rand::{Rng, thread_rng};
+use rand::{Rng, RngCore, SeedableRng, thread_rng};
+use rand::rngs::StdRng;
 use rand::seq::IteratorRandom;
 use rayon::prelude::*;
 
+use structure::time::TimeUnit::Minutes;
+use structure::time::{Time, TimeUnit};
+
+use crate::game::board::Chunk;
 use crate::game::pathogen::infection::Infection;
-use crate::game::population::{Person, Population};
+use crate::game::population::{
+    read_person, try_write_person, Condition, Person, Population,
+};
+use crate::game::population::person_behavior::tracing::ContactLog;
+use crate::game::population::person_behavior::transmission::TransmissionTree;
 use crate::game::population::person_behavior::Controller;
 use crate::game::roll;
 
+/// The density of a chunk at which each infected person makes a single contact per run
+pub const REFERENCE_DENSITY: f64 = 100.0;
+/// The default expected amount of contacts each infected person makes per game minute
+pub const DEFAULT_CONTACT_RATE: f64 = 1.0;
+/// The default fraction of their contacts that someone who needs a hospital still makes
+pub const DEFAULT_NEEDS_HOSPITAL_CONTACTS: f64 = 0.5;
+/// The default fraction of their contacts that someone in a hospital still makes
+pub const DEFAULT_HOSPITALIZED_CONTACTS: f64 = 0.0;
+
+/// The expected amount of contacts above which Poisson draws are approximated by a normal
+/// distribution, since e^-rate gets too small for Knuth's algorithm
+pub const POISSON_NORMAL_CUTOFF: f64 = 30.0;
+
+/// How the amount of contacts an infected person makes each run is drawn from the expected amount.
+/// Every distribution averages out to the expected amount.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum ContactDistribution {
+    /// The expected amount every run, where a fractional part is the chance of one more contact
+    #[default]
+    Fixed,
+    /// Anywhere from none to twice the expected amount. With 3 expected contacts this is close to
+    /// the `gen_range(0, 7)` contacts every infected person used to make before contact rates
+    Uniform,
+    /// Poisson distributed around the expected amount
+    Poisson,
+}
+
+impl ContactDistribution {
+    /// Draws the amount of contacts a single infected person makes, given the expected amount
+    pub fn sample<R: Rng>(&self, rate: f64, rng: &mut R) -> usize {
+        if rate < f64::EPSILON {
+            return 0;
+        }
+        match self {
+            ContactDistribution::Fixed => round_randomly(rate, rng),
+            ContactDistribution::Uniform => {
+                let rate = rng.gen_range(0.0, 2.0 * rate);
+                round_randomly(rate, rng)
+            }
+            ContactDistribution::Poisson if rate > POISSON_NORMAL_CUTOFF => {
+                // Box-Muller transform for a standard normal sample, scaled to the rate
+                let radius = (-2.0 * (1.0 - rng.gen::<f64>()).ln()).sqrt();
+                let normal = radius * (2.0 * std::f64::consts::PI * rng.gen::<f64>()).cos();
+                round_randomly(f64::max(0.0, rate + normal * rate.sqrt()), rng)
+            }
+            ContactDistribution::Poisson => {
+                // Knuth's algorithm, multiplying uniform samples until they pass e^-rate
+                let limit = (-rate).exp();
+                let mut count = 0;
+                let mut product = rng.gen::<f64>();
+                while product > limit {
+                    count += 1;
+                    product *= rng.gen::<f64>();
+                }
+                count
+            }
+        }
+    }
+}
+
+/// Rounds `value` up with a chance equal to its fractional part, so it's exact on average
+fn round_randomly<R: Rng>(value: f64, rng: &mut R) -> usize {
+    let mut count = value.floor() as usize;
+    if rng.gen_bool(value.fract()) {
+        count += 1;
+    }
+    count
+}
+
+///
+/// Decides the outcome of a contact between an infected person and someone else. The controller
+/// calls its rule once for every contact it makes, so a rule can keep track of past contacts to
+/// model things like repeated exposure.
+///
+/// Rules are shared between threads, so any state has to be kept behind `&self`
+pub trait InteractionRule: Send + Sync {
+    /// Lets `infected` try to pass their infections on to `other`, logging any transmission to
+    /// `recorder`. The contact happens at `time_of_year`, for seasonal pathogens. Every roll
+    /// should be made with `rng`, so that seeded runs stay reproducible.
+    ///
+    /// ###Return
+    /// Whether the other person just became infected
+    fn interact(
+        &self,
+        infected: &Person,
+        other: &mut Person,
+        recorder: Option<&TransmissionTree>,
+        time_of_year: &TimeUnit,
+        rng: &mut dyn RngCore,
+    ) -> bool;
+
+    /// Called by the controller after every run with the `minutes` of game time the run stood
+    /// for, so rules can keep their own clock
+    fn advance(&self, _minutes: usize) {}
+
+    /// Creates a new rule with the same settings, without anything this one built up from earlier
+    /// runs, so a run can be repeated from the start without touching this rule
+    fn fresh(&self) -> Arc<dyn InteractionRule>;
+}
+
+/// The default rule, where each active infection gets a single roll of its catch chance to spread
+#[derive(Debug, Copy, Clone, Default)]
+pub struct CatchChanceRule;
+
+impl InteractionRule for CatchChanceRule {
+    fn interact(
+        &self,
+        infected: &Person,
+        other: &mut Person,
+        recorder: Option<&TransmissionTree>,
+        time_of_year: &TimeUnit,
+        mut rng: &mut dyn RngCore,
+    ) -> bool {
+        infected
+            .interaction_outcome_at(other, recorder, Some(time_of_year), &mut rng)
+            .infected()
+    }
+
+    fn fresh(&self) -> Arc<dyn InteractionRule> {
+        Arc::new(CatchChanceRule)
+    }
+}
+
+///
+/// A rule where a single contact isn't enough to catch a pathogen. Everyone builds up exposure
+/// from their contacts with infectious people, which wears off over time between contacts, and
+/// only once it reaches the threshold do their contacts get a chance to infect them.
+///
+/// Exposure wears off by half every half-life of game time, going by a clock that the controller
+/// moves forward after every run. People who are already infected or immune aren't exposed.
+pub struct ExposureAccumulationRule {
+    threshold: f64,
+    half_life: usize, // in minutes
+    minute: AtomicUsize,
+    exposures: Mutex<HashMap<usize, (f64, usize)>>, // exposure of each person, and the minute it was last added to
+}
+
+impl ExposureAccumulationRule {
+    /// Creates a rule where it takes `threshold` contacts in short succession to get a chance to
+    /// be infected, and the exposure from every contact halves after `half_life`
+    ///
+    /// # Panics
+    /// Panics if `threshold` is not positive, or if `half_life` is shorter than a minute
+    pub fn new(threshold: f64, half_life: TimeUnit) -> Self {
+        if threshold <= 0.0 {
+            panic!("Invalid exposure threshold: {}", threshold);
+        }
+        let half_life = usize::from(half_life.into_minutes());
+        if half_life == 0 {
+            panic!("Invalid exposure half-life: {} minutes", half_life);
+        }
+        Self {
+            threshold,
+            half_life,
+            minute: AtomicUsize::new(0),
+            exposures: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The exposure at which contacts get a chance to infect someone
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    /// How long it takes for exposure to wear off by half
+    pub fn half_life(&self) -> TimeUnit {
+        Minutes(self.half_life)
+    }
+
+    /// The exposure that the person with this id had after their last contact
+    pub fn exposure(&self, id: usize) -> f64 {
+        self.exposures
+            .lock()
+            .unwrap()
+            .get(&id)
+            .map_or(0.0, |(exposure, _)| *exposure)
+    }
+
+    /// Wears off the person's exposure since their last contact and adds a contact to it
+    ///
+    /// ###Return
+    /// The person's exposure after the contact
+    fn expose(&self, id: usize) -> f64 {
+        let now = self.minute.load(SeqCst);
+        let mut exposures = self.exposures.lock().unwrap();
+        let (exposure, last) = exposures.entry(id).or_insert((0.0, now));
+        let elapsed = now - *last;
+        *exposure = *exposure * 0.5f64.powf(elapsed as f64 / self.half_life as f64) + 1.0;
+        *last = now;
+        *exposure
+    }
+}
+
+impl InteractionRule for ExposureAccumulationRule {
+    fn interact(
+        &self,
+        infected: &Person,
+        other: &mut Person,
+        recorder: Option<&TransmissionTree>,
+        time_of_year: &TimeUnit,
+        rng: &mut dyn RngCore,
+    ) -> bool {
+        // someone who can't spread anything yet doesn't expose anyone
+        if !(infected.infectious() || infected.shedding() || infected.dead()) {
+            return false;
+        }
+        // neither does anyone who can't catch anything from them
+        if other.infected()
+            || infected
+                .infections()
+                .iter()
+                .all(|i| other.immunity_to(i.get_pathogen()) >= 1.0)
+        {
+            return false;
+        }
+        if self.expose(other.id()) < self.threshold {
+            return false;
+        }
+        let infected_now = CatchChanceRule.interact(infected, other, recorder, time_of_year, rng);
+        if infected_now {
+            self.exposures.lock().unwrap().remove(&other.id());
+        }
+        infected_now
+    }
+
+    fn advance(&self, minutes: usize) {
+        self.minute.fetch_add(minutes, SeqCst);
+    }
+
+    fn fresh(&self) -> Arc<dyn InteractionRule> {
+        Arc::new(ExposureAccumulationRule::new(self.threshold, Minutes(self.half_life)))
+    }
+}
+
+/// The fraction of their contacts that people in each condition still make
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct ConditionContacts {
+    normal: f64,
+    needs_hospital: f64,
+    hospitalized: f64,
+}
+
+impl ConditionContacts {
+    fn scale(&self, condition: Condition) -> f64 {
+        match condition {
+            Condition::Normal => self.normal,
+            Condition::NeedsHospital => self.needs_hospital,
+            Condition::Hospitalized => self.hospitalized,
+        }
+    }
+}
+
+/// Everything that decides how an [InteractionController] runs besides its population, its seed
+/// and what it logs to, so that runs can be repeated with the same settings
+#[derive(Clone)]
+pub struct InteractionSettings {
+    contact_rate: f64,
+    time_step: usize,
+    condition_contacts: ConditionContacts,
+    contact_distribution: ContactDistribution,
+    assortativity: Option<(usize, f64)>,
+    rule: Arc<dyn InteractionRule>,
+}
+
+impl InteractionSettings {
+    /// The rule that decides the outcome of every contact
+    pub fn interaction_rule(&self) -> &Arc<dyn InteractionRule> {
+        &self.rule
+    }
+
+    /// Gets the same settings with `rule` deciding the outcome of every contact instead
+    pub fn with_interaction_rule(&self, rule: Arc<dyn InteractionRule>) -> Self {
+        Self {
+            rule,
+            ..self.clone()
+        }
+    }
+}
+
+impl PartialEq for InteractionSettings {
+    /// Rules are only equal if they are the same shared rule
+    fn eq(&self, other: &Self) -> bool {
+        self.contact_rate == other.contact_rate
+            && self.time_step == other.time_step
+            && self.condition_contacts == other.condition_contacts
+            && self.contact_distribution == other.contact_distribution
+            && self.assortativity == other.assortativity
+            && Arc::ptr_eq(&self.rule, &other.rule)
+    }
+}
+
 pub struct InteractionController {
     population: Arc<Mutex<Population>>,
+    recorder: Option<Arc<TransmissionTree>>,
+    contact_rate: f64, // expected amount of contacts per infected person per game minute
+    time_step: usize,  // in minutes, the game time each run stands for
+    rng: Option<StdRng>, // when set, runs are sequential and reproducible
+    condition_contacts: ConditionContacts,
+    contact_distribution: ContactDistribution,
+    assortativity: Option<(usize, f64)>, // age group width in years, and chance to turn down others
+    rule: Arc<dyn InteractionRule>,
+    contact_log: Option<Arc<ContactLog>>,
 }
 
 impl InteractionController {
     pub fn new(population: &Arc<Mutex<Population>>) -> Self {
         Self {
             population: population.clone(),
+            recorder: None,
+            contact_rate: DEFAULT_CONTACT_RATE,
+            time_step: 1,
+            rng: None,
+            condition_contacts: ConditionContacts {
+                normal: 1.0,
+                needs_hospital: DEFAULT_NEEDS_HOSPITAL_CONTACTS,
+                hospitalized: DEFAULT_HOSPITALIZED_CONTACTS,
+            },
+            contact_distribution: ContactDistribution::default(),
+            assortativity: None,
+            rule: Arc::new(CatchChanceRule),
+            contact_log: None,
+        }
+    }
+
+    /// Logs every successful transmission into `recorder`
+    ///
+    /// Each call to `run` moves the recorder forward by the time step in minutes, so every tick
+    /// of the recorder is a game minute
+    pub fn record_transmissions(&mut self, recorder: &Arc<TransmissionTree>) {
+        self.recorder = Some(recorder.clone());
+    }
+
+    pub fn recorder(&self) -> Option<&Arc<TransmissionTree>> {
+        self.recorder.as_ref()
+    }
+
+    /// Logs every contact into `log`, whether or not anything spread, so that contacts can be
+    /// traced
+    ///
+    /// Each call to `run` moves the log's clock forward by the time step in minutes
+    pub fn log_contacts(&mut self, log: &Arc<ContactLog>) {
+        self.contact_log = Some(log.clone());
+    }
+
+    pub fn contact_log(&self) -> Option<&Arc<ContactLog>> {
+        self.contact_log.as_ref()
+    }
+
+    /// The expected amount of contacts each infected person makes per game minute
+    pub fn contact_rate(&self) -> f64 {
+        self.contact_rate
+    }
+
+    /// The game time that every run stands for
+    pub fn time_step(&self) -> TimeUnit {
+        Minutes(self.time_step)
+    }
+
+    /// Makes every run stand for `step` of game time instead of a single minute, scaling the
+    /// contacts made per run to match, so long simulations need fewer runs
+    ///
+    /// # Panics
+    /// Panics if `step` is shorter than a minute
+    pub fn set_time_step(&mut self, step: TimeUnit) {
+        let minutes = usize::from(step.into_minutes());
+        if minutes == 0 {
+            panic!("Invalid time step: {} minutes", minutes);
+        }
+        self.time_step = minutes;
+    }
+
+    /// Scales the contact rate with the density of the chunk the population lives in, so crowded
+    /// chunks spread faster than sparse ones
+    ///
+    /// # Panics
+    /// Panics if the chunk has no size, which would make the contact rate infinite
+    pub fn scale_to_chunk(&mut self, chunk: &Chunk) {
+        if chunk.size() <= 0.0 || chunk.size().is_nan() {
+            panic!("Invalid chunk size: {}", chunk.size());
+        }
+        self.contact_rate = chunk.density() / REFERENCE_DENSITY;
+    }
+
+    /// How the amount of contacts each infected person makes is drawn every run
+    pub fn contact_distribution(&self) -> ContactDistribution {
+        self.contact_distribution
+    }
+
+    /// Sets how the amount of contacts each infected person makes is drawn every run
+    pub fn set_contact_distribution(&mut self, distribution: ContactDistribution) {
+        self.contact_distribution = distribution;
+    }
+
+    /// The fraction of their contacts that infected people in `condition` still make
+    pub fn condition_contact_scale(&self, condition: Condition) -> f64 {
+        self.condition_contacts.scale(condition)
+    }
+
+    /// Sets the fraction of their contacts that infected people in `condition` still make
+    ///
+    /// # Panics
+    /// Panics if `scale` is not within the range [0, 1]
+    pub fn set_condition_contact_scale(&mut self, condition: Condition, scale: f64) {
+        if !(0.0..=1.0).contains(&scale) {
+            panic!("Invalid condition contact scale: {}", scale);
+        }
+        match condition {
+            Condition::Normal => self.condition_contacts.normal = scale,
+            Condition::NeedsHospital => self.condition_contacts.needs_hospital = scale,
+            Condition::Hospitalized => self.condition_contacts.hospitalized = scale,
+        }
+    }
+
+    /// Makes people prefer contacts their own age. People are grouped by age into groups that are
+    /// `bin_size` years wide, and a contact with someone outside of the infected person's group is
+    /// turned down with a chance of `assortativity`, in which case someone else is picked.
+    ///
+    /// # Panics
+    /// Panics if `bin_size` is 0, or if `assortativity` is not within the range [0, 1)
+    pub fn set_age_assortativity(&mut self, bin_size: usize, assortativity: f64) {
+        if bin_size == 0 {
+            panic!("Invalid bin size: {}", bin_size);
+        }
+        if !(0.0..1.0).contains(&assortativity) {
+            panic!("Invalid assortativity: {}", assortativity);
+        }
+        self.assortativity = Some((bin_size, assortativity));
+    }
+
+    /// The rule that decides the outcome of every contact
+    pub fn interaction_rule(&self) -> &Arc<dyn InteractionRule> {
+        &self.rule
+    }
+
+    /// Replaces the rule that decides the outcome of every contact, which is [CatchChanceRule]
+    /// by default. The rule is shared, so its state can still be read from outside the controller
+    pub fn set_interaction_rule(&mut self, rule: Arc<dyn InteractionRule>) {
+        self.rule = rule;
+    }
+
+    /// Gets the current settings, which can be given to another controller with
+    /// [InteractionController::apply_settings]
+    pub fn settings(&self) -> InteractionSettings {
+        InteractionSettings {
+            contact_rate: self.contact_rate,
+            time_step: self.time_step,
+            condition_contacts: self.condition_contacts,
+            contact_distribution: self.contact_distribution,
+            assortativity: self.assortativity,
+            rule: self.rule.clone(),
+        }
+    }
+
+    /// Runs with `settings` from now on
+    pub fn apply_settings(&mut self, settings: &InteractionSettings) {
+        self.contact_rate = settings.contact_rate;
+        self.time_step = settings.time_step;
+        self.condition_contacts = settings.condition_contacts;
+        self.contact_distribution = settings.contact_distribution;
+        self.assortativity = settings.assortativity;
+        self.rule = settings.rule.clone();
+    }
+
+    /// Makes every following run sequential and driven by a rng seeded with `seed`, so that the
+    /// same scenario always spreads the same way
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = Some(StdRng::seed_from_u64(seed));
+    }
+
+    /// Whether runs are sequential and reproducible
+    pub fn deterministic(&self) -> bool {
+        self.rng.is_some()
+    }
+
+    /// Runs the interactions one infected person at a time, in the order of their ids, using the
+    /// controller's rng for every roll. If no seed was set, the rng is seeded from entropy.
+    pub fn run_sequential(&mut self) {
+        let mut rng = self.rng.take().unwrap_or_else(StdRng::from_entropy);
+        let mut _population = self
+            .population
+            .lock()
+            .expect("Should have been able to receive population");
+        let population = &mut *_population;
+
+        let contact_rate =
+            self.contact_rate * population.contact_scale() * self.time_step as f64;
+        let condition_contacts = self.condition_contacts;
+        let distribution = self.contact_distribution;
+        let recorder = self.recorder.as_deref();
+        let assortativity = self.assortativity;
+        let rule = &*self.rule;
+        let time_of_year = population.time_of_year();
+        let matrix = population.interaction_recorder().cloned();
+        let contact_log = self.contact_log.as_deref();
+        let mut infected = population
+            .get_infected()
+            .iter()
+            .chain(population.get_infectious_dead())
+            .cloned()
+            .collect::<Vec<_>>();
+        infected.sort_by_key(|p| read_person(p).id());
+
+        let mut new_add = vec![];
+        for person in &infected {
+            let infected = &*read_person(person);
+            let (rate, chance) = contact_odds(infected, contact_rate, &condition_contacts);
+            let count = distribution.sample(rate, &mut rng);
+
+            for _ in 0..count {
+                if !rng.gen_bool(chance) {
+                    continue;
+                }
+                let everyone = population.get_everyone();
+                if everyone.is_empty() {
+                    break;
+                }
+                let mut arc = &everyone[rng.gen_range(0, everyone.len())];
+                while arc
+                    .try_read()
+                    .is_ok_and(|other| turned_down(assortativity, infected, &other, &mut rng))
+                {
+                    arc = &everyone[rng.gen_range(0, everyone.len())];
+                }
+                if let Some(mut other) = try_write_person(arc) {
+                    if other.id() == infected.id() {
+                        continue;
+                    }
+                    if let Some(matrix) = &matrix {
+                        matrix.record(
+                            infected.get_age_years() as usize,
+                            other.get_age_years() as usize,
+                        );
+                    }
+                    if let Some(log) = contact_log {
+                        log.record(infected.id(), other.id());
+                    }
+                    let was_infected = other.infected();
+                    if rule.interact(infected, &mut other, recorder, &time_of_year, &mut rng)
+                        && !was_infected
+                    {
+                        new_add.push(arc.clone());
+                    }
+                }
+            }
+        }
+        population.infected.extend(new_add);
+
+        if let Some(recorder) = recorder {
+            recorder.advance(self.time_step);
         }
+        if let Some(log) = contact_log {
+            log.advance(self.time_step);
+        }
+        self.rule.advance(self.time_step);
+        self.rng = Some(rng);
     }
 }
 
 const INTERACTION_CHANCE: f64 = 1.0;
 
+/// The expected amount of contacts `infected` makes in a run, and the chance that each of them
+/// actually happens. The condition of the infected only scales how many contacts they make, while
+/// their severity and quarantine hold back each contact. The body of someone who died is handled
+/// by others as if they were well, so it's held back by neither health nor behavior.
+fn contact_odds(
+    infected: &Person,
+    contact_rate: f64,
+    condition_contacts: &ConditionContacts,
+) -> (f64, f64) {
+    if infected.dead() {
+        return (
+            contact_rate * condition_contacts.scale(Condition::Normal),
+            INTERACTION_CHANCE,
+        );
+    }
+    let activity = if infected.quarantined() {
+        0.0
+    } else {
+        1.0 - infected.severity()
+    };
+    (
+        contact_rate * condition_contacts.scale(infected.condition_state()),
+        INTERACTION_CHANCE * activity * infected.condition(),
+    )
+}
+
+/// Whether `infected` turns down a contact with `other` for being outside of their age group
+fn turned_down<R: Rng>(
+    assortativity: Option<(usize, f64)>,
+    infected: &Person,
+    other: &Person,
+    rng: &mut R,
+) -> bool {
+    match assortativity {
+        Some((bin_size, chance)) => {
+            infected.get_age_years() as usize / bin_size != other.get_age_years() as usize / bin_size
+                && rng.gen_bool(chance)
+        }
+        None => false,
+    }
+}
+
 impl Controller for InteractionController {
     fn run(&mut self) {
+        if self.deterministic() {
+            self.run_sequential();
+            return;
+        }
         let mut _population = self
             .population
             .lock()
@@ -34,31 +638,33 @@ impl Controller for InteractionController {
 
         let mut new_add = Arc::new(Mutex::new(vec![]));
         let pop_size = population.get_total_population();
+        let contact_rate =
+            self.contact_rate * population.contact_scale() * self.time_step as f64;
+        let condition_contacts = self.condition_contacts;
+        let distribution = self.contact_distribution;
+        let recorder = self.recorder.as_deref();
+        let assortativity = self.assortativity;
+        let rule = &*self.rule;
+        let time_of_year = population.time_of_year();
+        let matrix = population.interaction_recorder().cloned();
+        let contact_log = self.contact_log.as_deref();
 
         population
             .get_infected()
             .iter()
+            .chain(population.get_infectious_dead())
             .par_bridge()
             .for_each(|person| {
-                let infected = &*match person.read() {
-                    Ok(i) => i,
-                    Err(_) => panic!("Poisoned"),
-                };
-
-                let severity = {
-                    let guard = infected.infection.lock().unwrap();
-                    match &*guard {
-                        None => panic!("There should be an infection"),
+                let infected = &*read_person(person);
 
-                        Some(ref i) => i.get_pathogen().severity(),
-                    }
-                };
-
-                let severity_effect = 1.0 - severity;
-                let count = 1; // thread_rng().gen_range(0, 7);
+                if infected.never_infected() {
+                    panic!("There should be an infection")
+                }
+                let (rate, chance) = contact_odds(infected, contact_rate, &condition_contacts);
+                let count = distribution.sample(rate, &mut thread_rng());
 
                 'outer: for _ in 0..count {
-                    if roll(INTERACTION_CHANCE * severity_effect * infected.condition()) {
+                    if roll(chance) {
                         // Whether the person actually interacts with a person
 
                         if let Some((arc, mut other)) = {
@@ -66,6 +672,9 @@ impl Controller for InteractionController {
                                 let mut output = None;
                                 'inner: for i in 0..pop_size {
                                     let everyone = population.get_everyone();
+                                    if everyone.is_empty() {
+                                        break 'inner;
+                                    } // everyone has died, so there's nobody to interact with
                                     let roll = thread_rng().gen_range(0, everyone.len()); // randomly choose a person
                                     let arc = everyone.get(roll);
 
@@ -75,13 +684,24 @@ impl Controller for InteractionController {
 
                                     let mut arc = arc.unwrap(); // we know we have some value
 
-                                    match arc.try_write() {
+                                    match try_write_person(arc) {
                                         // if we can get write access, we can infect it
-                                        Ok(write_guard) => {
+                                        Some(write_guard) => {
+                                            if write_guard.id() == infected.id() {
+                                                continue;
+                                            } // the infected person can't interact with themselves
+                                            if turned_down(
+                                                assortativity,
+                                                infected,
+                                                &write_guard,
+                                                &mut thread_rng(),
+                                            ) {
+                                                continue;
+                                            }
                                             output = Some((arc, write_guard));
                                             break 'inner;
                                         }
-                                        Err(_) => {}
+                                        None => {}
                                     }
                                 }
                                 output
@@ -89,7 +709,24 @@ impl Controller for InteractionController {
 
                             output
                         } {
-                            if infected.interact_with(&mut *other) {
+                            if let Some(matrix) = &matrix {
+                                matrix.record(
+                                    infected.get_age_years() as usize,
+                                    other.get_age_years() as usize,
+                                );
+                            }
+                            if let Some(log) = contact_log {
+                                log.record(infected.id(), other.id());
+                            }
+                            let was_infected = other.infected();
+                            if rule.interact(
+                                infected,
+                                &mut other,
+                                recorder,
+                                &time_of_year,
+                                &mut thread_rng(),
+                            ) && !was_infected
+                            {
                                 // performs an interaction with the other person
                                 // person was infected
 
@@ -106,8 +743,687 @@ impl Controller for InteractionController {
         for person in &*new_add.lock().unwrap() {
             population.infected.push(person.clone());
         }
+
+        if let Some(recorder) = recorder {
+            recorder.advance(self.time_step);
+        }
+        if let Some(log) = contact_log {
+            log.advance(self.time_step);
+        }
+        self.rule.advance(self.time_step);
     }
 }
 
 #[cfg(test)]
-mod test {}
+mod test {
+    use std::collections::{HashMap, HashSet};
+    use std::sync::{Arc, Mutex};
+
+    use rand::rngs::StdRng;
+    use rand::{RngCore, SeedableRng};
+
+    use structure::graph::Graph;
+    use structure::time::{Time, TimeUnit};
+    use structure::time::TimeUnit::{Days, Hours, Minutes, Years};
+
+    use crate::game::board::Chunk;
+    use crate::game::pathogen::symptoms::base::cheat::CustomCatchChance;
+    use crate::game::pathogen::symptoms::Symp;
+    use crate::game::pathogen::types::{PathogenType, Virus};
+    use crate::game::pathogen::Pathogen;
+
+    use crate::game::population::person_behavior::interaction::{
+        contact_odds, CatchChanceRule, ContactDistribution, ExposureAccumulationRule,
+        InteractionController, InteractionRule, DEFAULT_NEEDS_HOSPITAL_CONTACTS,
+    };
+    use crate::game::population::person_behavior::transmission::{Transmission, TransmissionTree};
+    use crate::game::population::person_behavior::Controller;
+    use crate::game::population::Sex::Male;
+    use crate::game::population::{
+        Condition, HealthPoints, MortalityModel, Person, PersonBuilder, Population,
+        UniformDistribution,
+    };
+    use crate::game::{Age, Update};
+
+    #[test]
+    fn transmission_tree_is_rooted_at_seed() {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            200,
+            UniformDistribution::new(20, 60),
+        );
+        let mut pathogen = Virus.create_pathogen("Test", 0);
+        pathogen.acquire_symptom(&CustomCatchChance(99.0).get_symptom(), None);
+        let pathogen = Arc::new(pathogen);
+
+        assert!(pop.infect_one(&pathogen));
+        let seed = pop.get_infected()[0].read().unwrap().id();
+
+        let pop_arc = Arc::new(Mutex::new(pop));
+        let tree = Arc::new(TransmissionTree::new());
+        let mut controller = InteractionController::new(&pop_arc);
+        controller.record_transmissions(&tree);
+
+        for _ in 0..2000 {
+            {
+                let mut guard = pop_arc.lock().unwrap();
+                if guard.get_infected().is_empty() {
+                    break;
+                }
+                guard.update(20);
+            }
+            controller.run();
+        }
+
+        assert!(!tree.is_empty(), "The seed never infected anyone");
+        assert_eq!(tree.roots(), vec![seed]);
+
+        let mut infector_counts: HashMap<usize, usize> = HashMap::new();
+        for transmission in tree.transmissions() {
+            *infector_counts.entry(transmission.infectee).or_insert(0) += 1;
+        }
+        assert!(!infector_counts.contains_key(&seed));
+        for (infectee, count) in infector_counts {
+            assert_eq!(count, 1, "Person {} had {} infectors", infectee, count);
+        }
+    }
+
+    /// Gets how many rounds of interactions it takes for half of a population living in a chunk
+    /// of `size` to be infected
+    fn rounds_to_infect_half(size: f64) -> usize {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            500,
+            UniformDistribution::new(20, 60),
+        );
+        let mut pathogen = Virus.create_pathogen("Test", 0);
+        pathogen.acquire_symptom(&CustomCatchChance(99.0).get_symptom(), None);
+        let pathogen = Arc::new(pathogen);
+        assert!(pop.infect_one(&pathogen));
+
+        let pop_arc = Arc::new(Mutex::new(pop));
+        let mut controller = InteractionController::new(&pop_arc);
+        controller.scale_to_chunk(&Chunk::new(500, size));
+
+        let mut rounds = 0;
+        while pop_arc.lock().unwrap().get_all_ever_infected() < 250 && rounds < 5000 {
+            pop_arc.lock().unwrap().update(20);
+            controller.run();
+            rounds += 1;
+        }
+        rounds
+    }
+
+    #[test]
+    fn dense_chunks_spread_faster() {
+        let chunk = Chunk::new(500, 2.0);
+        assert_eq!(chunk.density(), 250.0);
+
+        let dense = rounds_to_infect_half(1.0);
+        let sparse = rounds_to_infect_half(10.0);
+        assert!(
+            dense < sparse,
+            "Dense chunk took {} rounds, sparse chunk took {}",
+            dense,
+            sparse
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn empty_chunks_are_rejected() {
+        let pop = Arc::new(Mutex::new(Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            10,
+            UniformDistribution::new(20, 60),
+        )));
+        InteractionController::new(&pop).scale_to_chunk(&Chunk::new(10, 0.0));
+    }
+
+    /// Runs a seeded outbreak on the population from a clean slate and gets its transmissions
+    fn seeded_outbreak(pop: &Arc<Mutex<Population>>, seed: u64) -> Vec<Transmission> {
+        let mut pathogen = Virus.create_pathogen("Test", 0);
+        pathogen.acquire_symptom(&CustomCatchChance(99.0).get_symptom(), None);
+        pathogen.set_dose_fraction(Some(1.0));
+        let pathogen = Arc::new(pathogen);
+        {
+            let mut guard = pop.lock().unwrap();
+            guard.reset();
+            // a dose large enough to be spreading right away, which is passed on in full
+            let first = guard.get_everyone()[0].clone();
+            assert!(first.write().unwrap().infect_with_dose(&pathogen, 30_000_000));
+            guard.rebuild_infected();
+        }
+
+        let tree = Arc::new(TransmissionTree::new());
+        let mut controller = InteractionController::new(pop);
+        controller.record_transmissions(&tree);
+        controller.set_seed(seed);
+        for _ in 0..40 {
+            controller.run();
+        }
+        tree.transmissions()
+    }
+
+    #[test]
+    fn seeded_runs_are_reproducible() {
+        let pop = Arc::new(Mutex::new(Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            300,
+            UniformDistribution::new(20, 60),
+        )));
+
+        let first = seeded_outbreak(&pop, 11);
+        assert!(first.len() > 1, "Only {} transmissions", first.len());
+        assert!(first.iter().any(|t| t.infector != first[0].infector));
+        assert_eq!(first, seeded_outbreak(&pop, 11));
+        assert_ne!(first, seeded_outbreak(&pop, 12));
+    }
+
+    #[test]
+    fn infected_never_interacts_with_themselves() {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            1,
+            UniformDistribution::new(20, 60),
+        );
+        let mut pathogen = Virus.create_pathogen("Test", 0);
+        pathogen.acquire_symptom(&CustomCatchChance(99.0).get_symptom(), None);
+        let pathogen = Arc::new(pathogen);
+        let only = pop.get_everyone()[0].clone();
+        assert!(only.write().unwrap().infect_with_dose(&pathogen, 30_000_000));
+        pop.rebuild_infected();
+
+        let pop = Arc::new(Mutex::new(pop));
+        let tree = Arc::new(TransmissionTree::new());
+        let mut controller = InteractionController::new(&pop);
+        controller.record_transmissions(&tree);
+        controller.scale_to_chunk(&Chunk::new(1, 0.001));
+        for run in 0..100 {
+            if run == 50 {
+                controller.set_seed(3);
+            }
+            controller.run();
+        }
+
+        assert!(tree.is_empty());
+        assert_eq!(pop.lock().unwrap().get_infected().len(), 1);
+    }
+
+    /// Gets how many people a single infected person in `condition` infects over a few runs
+    fn infected_by_one(condition: Condition) -> usize {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            300,
+            UniformDistribution::new(20, 60),
+        );
+        let mut pathogen = Virus.create_pathogen("Test", 0);
+        pathogen.acquire_symptom(&CustomCatchChance(99.0).get_symptom(), None);
+        let pathogen = Arc::new(pathogen);
+        let first = pop.get_everyone()[0].clone();
+        {
+            let mut first = first.write().unwrap();
+            assert!(first.infect_with_dose(&pathogen, 30_000_000));
+            *first.condition.lock().unwrap() = condition;
+        }
+        pop.rebuild_infected();
+
+        let pop = Arc::new(Mutex::new(pop));
+        let tree = Arc::new(TransmissionTree::new());
+        let mut controller = InteractionController::new(&pop);
+        controller.record_transmissions(&tree);
+        controller.scale_to_chunk(&Chunk::new(300, 1.0));
+        for _ in 0..20 {
+            controller.run();
+        }
+        tree.len()
+    }
+
+    #[test]
+    fn hospitalized_infectors_make_no_contacts() {
+        assert!(infected_by_one(Condition::Normal) > 0);
+        assert_eq!(infected_by_one(Condition::Hospitalized), 0);
+    }
+
+    #[test]
+    fn condition_only_scales_contacts_once() {
+        let mut person = Person::new(0, Age::new(30, 0, 0), Male, 1.0);
+        let pathogen = Arc::new(Virus.create_pathogen("Test", 0));
+        assert!(person.infect(&pathogen));
+        let controller = InteractionController::new(&Arc::new(Mutex::new(Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            0,
+            UniformDistribution::new(20, 60),
+        ))));
+        let expected_contacts = |person: &Person| {
+            let (rate, chance) = contact_odds(person, 1.0, &controller.condition_contacts);
+            rate * chance
+        };
+
+        let normal = expected_contacts(&person);
+        assert!(normal > 0.0);
+        *person.condition.lock().unwrap() = Condition::NeedsHospital;
+        let needs_hospital = expected_contacts(&person);
+        assert!((needs_hospital / normal - DEFAULT_NEEDS_HOSPITAL_CONTACTS).abs() < 1e-9);
+    }
+
+    /// Gets the mean amount of contacts drawn from `distribution` when 2.5 are expected
+    fn mean_contacts(distribution: ContactDistribution) -> f64 {
+        let mut rng = StdRng::seed_from_u64(9);
+        let total = (0..20_000)
+            .map(|_| distribution.sample(2.5, &mut rng))
+            .sum::<usize>();
+        total as f64 / 20_000.0
+    }
+
+    #[test]
+    fn contact_distributions_average_to_rate() {
+        for distribution in [
+            ContactDistribution::Fixed,
+            ContactDistribution::Uniform,
+            ContactDistribution::Poisson,
+        ] {
+            let mean = mean_contacts(distribution);
+            assert!(
+                (mean - 2.5).abs() < 0.05,
+                "{:?} averaged {} contacts",
+                distribution,
+                mean
+            );
+        }
+
+        let mut rng = StdRng::seed_from_u64(9);
+        assert_eq!(ContactDistribution::Fixed.sample(1.0, &mut rng), 1);
+        assert_eq!(ContactDistribution::Poisson.sample(0.0, &mut rng), 0);
+        assert!((0..100).any(|_| ContactDistribution::Poisson.sample(1.0, &mut rng) > 2));
+    }
+
+    #[test]
+    fn large_poisson_rates_average_to_rate() {
+        let mut rng = StdRng::seed_from_u64(2155);
+        let total = (0..2000)
+            .map(|_| ContactDistribution::Poisson.sample(1000.0, &mut rng))
+            .sum::<usize>();
+        let mean = total as f64 / 2000.0;
+        assert!((mean - 1000.0).abs() < 5.0, "Averaged {} contacts", mean);
+    }
+
+    /// Spreads a pathogen from 10 people for 12 hours, running the controller either 60 times an
+    /// hour with the default step or once an hour with an hourly step, and gets how many people
+    /// were infected
+    fn infected_after_half_a_day(hourly: bool) -> usize {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            1000,
+            UniformDistribution::new(20, 60),
+        );
+        pop.set_contact_scale(0.01);
+        let mut pathogen = Virus.create_pathogen("Test", 0);
+        pathogen.acquire_symptom(&CustomCatchChance(99.0).get_symptom(), None);
+        let pathogen = Arc::new(pathogen);
+        for person in pop.get_everyone().iter().take(10) {
+            assert!(person.write().unwrap().infect_with_dose(&pathogen, 30_000_000));
+        }
+        pop.rebuild_infected();
+
+        let pop = Arc::new(Mutex::new(pop));
+        let mut controller = InteractionController::new(&pop);
+        if hourly {
+            controller.set_time_step(Hours(1));
+        }
+        let runs_per_hour = if hourly { 1 } else { 60 };
+        for _ in 0..12 {
+            pop.lock().unwrap().update(20 * 60);
+            for _ in 0..runs_per_hour {
+                controller.run();
+            }
+        }
+        let pop = pop.lock().unwrap();
+        pop.get_all_ever_infected()
+    }
+
+    #[test]
+    fn hourly_steps_match_minute_steps() {
+        let minutely = (0..5).map(|_| infected_after_half_a_day(false)).sum::<usize>();
+        let hourly = (0..5).map(|_| infected_after_half_a_day(true)).sum::<usize>();
+        let ratio = hourly as f64 / minutely as f64;
+        assert!(
+            minutely > 5 * 10 && (0.75..1.33).contains(&ratio),
+            "Hourly steps infected {}, minute steps infected {}",
+            hourly,
+            minutely
+        );
+    }
+
+    #[test]
+    fn interacting_after_everyone_died() {
+        for seed in [None, Some(5)] {
+            let mut pop = Population::new(
+                &PersonBuilder::new(),
+                0.0,
+                50,
+                UniformDistribution::new(20, 60),
+            );
+            pop.set_mortality_model(MortalityModel::CaseFatality(1.0));
+            let pathogen = Arc::new(Virus.create_pathogen("Test", 0));
+            for _ in 0..50 {
+                assert!(pop.infect_one(&pathogen));
+            }
+
+            let pop = Arc::new(Mutex::new(pop));
+            let mut controller = InteractionController::new(&pop);
+            if let Some(seed) = seed {
+                controller.set_seed(seed);
+            }
+            while !pop.lock().unwrap().get_infected().is_empty() {
+                // interacts while the dead are still waiting to be cleaned up
+                pop.lock().unwrap().update_without_cleanup(20 * 60 * 6);
+                controller.run();
+                pop.lock().unwrap().cleanup();
+            }
+            {
+                // anyone who survived dies of something else
+                let mut pop = pop.lock().unwrap();
+                pop.par_for_each(|person| {
+                    *person.health_points().write().unwrap() = HealthPoints::new(0)
+                });
+                pop.cleanup();
+                assert!(pop.get_everyone().is_empty());
+            }
+            for _ in 0..5 {
+                controller.run();
+            }
+            assert_eq!(pop.lock().unwrap().compartments().dead, 50);
+        }
+    }
+
+    /// Records the contacts of 100 infected people in a population aged 20 to 60, split into
+    /// groups 20 years wide, over a few runs
+    fn recorded_mixing(assortativity: Option<f64>, seed: Option<u64>) -> Vec<Vec<usize>> {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            400,
+            UniformDistribution::new(20, 60),
+        );
+        let pathogen = Arc::new(Virus.default());
+        for _ in 0..100 {
+            assert!(pop.infect_one(&pathogen));
+        }
+        let matrix = pop.record_interactions(20);
+
+        let pop = Arc::new(Mutex::new(pop));
+        let mut controller = InteractionController::new(&pop);
+        if let Some(assortativity) = assortativity {
+            controller.set_age_assortativity(20, assortativity);
+        }
+        if let Some(seed) = seed {
+            controller.set_seed(seed);
+        }
+        for _ in 0..5 {
+            controller.run();
+        }
+        assert!(matrix.total() > 0);
+        let recorded = pop.lock().unwrap().interaction_matrix().unwrap();
+        assert_eq!(recorded, matrix.contacts());
+        recorded
+    }
+
+    #[test]
+    fn assortative_mixing_dominates_the_diagonal() {
+        for seed in [None, Some(3)] {
+            let contacts = recorded_mixing(Some(0.9), seed);
+            let diagonal: usize = (0..contacts.len()).map(|g| contacts[g][g]).sum();
+            let total: usize = contacts.iter().flatten().sum();
+            assert!(
+                diagonal > 3 * (total - diagonal),
+                "{} of {} contacts were within an age group",
+                diagonal,
+                total
+            );
+        }
+
+        let contacts = recorded_mixing(None, None);
+        let diagonal: usize = (0..contacts.len()).map(|g| contacts[g][g]).sum();
+        let total: usize = contacts.iter().flatten().sum();
+        assert!(diagonal < total);
+    }
+
+    /// Only lets an infection through on someone's second contact or later
+    #[derive(Default)]
+    struct RepeatedExposure {
+        contacts: Mutex<HashMap<usize, usize>>,
+    }
+
+    impl InteractionRule for RepeatedExposure {
+        fn interact(
+            &self,
+            infected: &Person,
+            other: &mut Person,
+            recorder: Option<&TransmissionTree>,
+            time_of_year: &TimeUnit,
+            rng: &mut dyn RngCore,
+        ) -> bool {
+            let contacts = {
+                let mut contacts = self.contacts.lock().unwrap();
+                let count = contacts.entry(other.id()).or_insert(0);
+                *count += 1;
+                *count
+            };
+            contacts >= 2 && CatchChanceRule.interact(infected, other, recorder, time_of_year, rng)
+        }
+
+        fn fresh(&self) -> Arc<dyn InteractionRule> {
+            Arc::new(RepeatedExposure::default())
+        }
+    }
+
+    #[test]
+    fn custom_rule_requires_repeated_exposure() {
+        for seed in [4, 2168] {
+            let mut pop = Population::new(
+                &PersonBuilder::new(),
+                0.0,
+                200,
+                UniformDistribution::new(20, 60),
+            );
+            let mut pathogen = Virus.create_pathogen("Test", 0);
+            pathogen.acquire_symptom(&CustomCatchChance(99.0).get_symptom(), None);
+            let pathogen = Arc::new(pathogen);
+            let first = pop.get_everyone()[0].clone();
+            assert!(first.write().unwrap().infect_with_dose(&pathogen, 30_000_000));
+            pop.rebuild_infected();
+
+            let pop = Arc::new(Mutex::new(pop));
+            let tree = Arc::new(TransmissionTree::new());
+            let rule = Arc::new(RepeatedExposure::default());
+            let mut controller = InteractionController::new(&pop);
+            controller.record_transmissions(&tree);
+            controller.set_interaction_rule(rule.clone());
+            controller.set_seed(seed);
+            for _ in 0..1000 {
+                controller.run();
+            }
+
+            let contacts = rule.contacts.lock().unwrap();
+            assert!(!tree.is_empty(), "Nobody was infected");
+            assert!(contacts.len() > tree.len(), "Everyone contacted was infected");
+            for transmission in tree.transmissions() {
+                let count = contacts[&transmission.infectee];
+                assert!(
+                    count >= 2,
+                    "Person {} was infected after {} contact",
+                    transmission.infectee,
+                    count
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn generation_intervals_fit_the_infectious_period() {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            300,
+            UniformDistribution::new(20, 60),
+        );
+        pop.set_contact_scale(0.01);
+        let mut pathogen = Virus.create_pathogen("Test", 0);
+        pathogen.acquire_symptom(&CustomCatchChance(99.0).get_symptom(), None);
+        let pathogen = Arc::new(pathogen);
+        let first = pop.get_everyone()[0].clone();
+        assert!(first.write().unwrap().infect_with_dose(&pathogen, 30_000_000));
+        pop.rebuild_infected();
+
+        let pop = Arc::new(Mutex::new(pop));
+        let tree = Arc::new(TransmissionTree::new());
+        let mut controller = InteractionController::new(&pop);
+        controller.record_transmissions(&tree);
+        controller.set_time_step(Hours(1));
+        for _ in 0..24 * 60 {
+            {
+                let mut guard = pop.lock().unwrap();
+                if guard.get_infected().is_empty() {
+                    break;
+                }
+                guard.update(20 * 60);
+            }
+            controller.run();
+        }
+
+        let intervals = tree.generation_intervals();
+        let seed = first.read().unwrap().id();
+        assert!(!intervals.is_empty(), "Nobody the seed infected spread it further");
+        assert_eq!(intervals.len(), tree.len() - tree.infected_by(seed).len());
+
+        // runs are an hour apart, and nobody spreads an infection in the run they caught it
+        let minutes = intervals
+            .iter()
+            .map(|interval| usize::from(interval.as_minutes()))
+            .collect::<Vec<_>>();
+        assert!(minutes.iter().all(|&m| m >= 60 && m % 60 == 0));
+        let mean = minutes.iter().sum::<usize>() / minutes.len();
+        let longest = pathogen.average_recovery_time() + pathogen.base_recovery_distance();
+        assert!(mean < longest, "Mean interval of {} minutes", mean);
+    }
+
+    #[test]
+    fn bodies_spread_after_the_last_case_dies() {
+        // gets how many people the body of the only case infected, and whether the body stopped
+        // being infectious a day later
+        let spread_after_death = |period: TimeUnit| {
+            let mut pop = Population::new(
+                &PersonBuilder::new(),
+                0.0,
+                200,
+                UniformDistribution::new(20, 60),
+            );
+            pop.set_mortality_model(MortalityModel::CaseFatality(1.0));
+            let mut p = Pathogen::new(
+                "Test".to_string(),
+                0,
+                0.0,
+                24 * 60,
+                0,
+                Graph::new(),
+                HashSet::new(),
+            );
+            p.acquire_symptom(&CustomCatchChance(99.0).get_symptom(), None);
+            p.set_post_mortem_infectious_period(period);
+            let pathogen = Arc::new(p);
+            assert!(pop.infect_one(&pathogen));
+            let case = pop.get_infected()[0].read().unwrap().id();
+            // the only case dies before meeting anyone
+            while !pop.get_infected().is_empty() {
+                pop.update(20 * 60);
+            }
+            assert_eq!(pop.statistics().dead, 1);
+
+            let pop = Arc::new(Mutex::new(pop));
+            let mut controller = InteractionController::new(&pop);
+            for _ in 0..10 {
+                controller.run();
+                pop.lock().unwrap().update(20);
+            }
+            let mut pop = pop.lock().unwrap();
+            let infected_by_body = pop.statistics().ever_infected - 1;
+            pop.update(20 * 24 * 60);
+            let body_stopped = pop
+                .get_infectious_dead()
+                .all(|p| p.read().unwrap().id() != case);
+            (infected_by_body, body_stopped)
+        };
+
+        assert_eq!(spread_after_death(Minutes(0)), (0, true));
+        let (infected_by_body, body_stopped) = spread_after_death(Days(1));
+        assert!(infected_by_body > 0, "The body never infected anyone");
+        assert!(body_stopped);
+    }
+
+    #[test]
+    fn exposure_accumulates_over_repeated_contacts() {
+        let mut pathogen = Virus.create_pathogen("Test", 0);
+        pathogen.acquire_symptom(&CustomCatchChance(99.0).get_symptom(), None);
+        let pathogen = Arc::new(pathogen);
+        let mut infected = Person::new(0, Age::new(30, 0, 0), Male, 1.0);
+        assert!(infected.infect_with_dose(&pathogen, 30_000_000));
+        assert!(infected.infectious());
+
+        let rule = ExposureAccumulationRule::new(3.0, Hours(12));
+        let mut rng = StdRng::seed_from_u64(2200);
+
+        // a single contact stays below the threshold
+        let mut once = Person::new(1, Age::new(30, 0, 0), Male, 1.0);
+        assert!(!rule.interact(&infected, &mut once, None, &Hours(0), &mut rng));
+        assert!(!once.infected());
+        assert_eq!(rule.exposure(1), 1.0);
+
+        // contacts spread out over days wear off before they add up
+        let mut spread_out = Person::new(2, Age::new(30, 0, 0), Male, 1.0);
+        for _ in 0..10 {
+            assert!(!rule.interact(&infected, &mut spread_out, None, &Hours(0), &mut rng));
+            rule.advance(usize::from(Days(3).into_minutes()));
+        }
+        assert!(!spread_out.infected());
+        assert!(rule.exposure(2) < 2.0);
+
+        // a year between contacts is a long time, not no time at all
+        let mut yearly = Person::new(4, Age::new(30, 0, 0), Male, 1.0);
+        assert!(!rule.interact(&infected, &mut yearly, None, &Hours(0), &mut rng));
+        rule.advance(usize::from(Years(1).into_minutes()));
+        assert!(!rule.interact(&infected, &mut yearly, None, &Hours(0), &mut rng));
+        assert!(rule.exposure(4) < 1.01);
+
+        // the infected and the immune aren't exposed at all
+        let mut sick = Person::new(5, Age::new(30, 0, 0), Male, 1.0);
+        assert!(sick.infect_with_dose(&pathogen, 30_000_000));
+        let mut immune = Person::new(6, Age::new(30, 0, 0), Male, 1.0);
+        assert!(immune.infect_with_dose(&pathogen, 30_000_000));
+        assert!(immune.cure(pathogen.name()));
+        for person in [&mut sick, &mut immune] {
+            assert!(!rule.interact(&infected, person, None, &Hours(0), &mut rng));
+            assert_eq!(rule.exposure(person.id()), 0.0);
+        }
+
+        // contacts in quick succession do
+        let mut repeated = Person::new(3, Age::new(30, 0, 0), Male, 1.0);
+        let mut contacts = 0;
+        while !repeated.infected() {
+            contacts += 1;
+            assert!(contacts <= 20, "Repeated contacts never infected anyone");
+            rule.interact(&infected, &mut repeated, None, &Hours(0), &mut rng);
+            rule.advance(1);
+        }
+        assert!(contacts >= 3, "Infected after only {} contacts", contacts);
+        assert_eq!(rule.exposure(3), 0.0);
+    }
+}
@@ -0,0 +1,166 @@
+use std::sync::{Arc, Mutex};
+
+use crate::game::population::person_behavior::Controller;
+use crate::game::population::Population;
+
+///
+/// Locks a population down for a scheduled period, cutting the contacts everyone makes by the
+/// fraction of people who comply, and restores the contacts once it's over. Only the lockdown's
+/// own cut is undone, so overlapping lockdowns and other changes to the contact scale are kept.
+///
+/// Each call to `run` counts as one tick of the schedule
+pub struct LockdownController {
+    population: Arc<Mutex<Population>>,
+    compliance: f64,
+    start: usize,
+    duration: usize,
+    tick: usize,
+    contact_factor: Option<usize>, // the handle of the lockdown's contact factor, while it's active
+}
+
+impl LockdownController {
+    /// Creates a lockdown that begins after `start` runs and lasts for `duration` runs
+    ///
+    /// # Panics
+    /// Panics if `compliance` is not within the range [0, 1]
+    pub fn new(
+        population: &Arc<Mutex<Population>>,
+        compliance: f64,
+        start: usize,
+        duration: usize,
+    ) -> Self {
+        if !(0.0..=1.0).contains(&compliance) {
+            panic!("Invalid compliance: {}", compliance);
+        }
+        Self {
+            population: population.clone(),
+            compliance,
+            start,
+            duration,
+            tick: 0,
+            contact_factor: None,
+        }
+    }
+
+    /// The fraction of contacts that are given up during the lockdown
+    pub fn compliance(&self) -> f64 {
+        self.compliance
+    }
+
+    /// Whether the lockdown is currently in effect
+    pub fn active(&self) -> bool {
+        self.contact_factor.is_some()
+    }
+}
+
+impl Controller for LockdownController {
+    fn run(&mut self) {
+        let mut population = self
+            .population
+            .lock()
+            .expect("Should have been able to receive population");
+
+        if self.tick == self.start && self.duration > 0 {
+            self.contact_factor = Some(population.add_contact_factor(1.0 - self.compliance));
+        } else if self.tick == self.start + self.duration {
+            if let Some(handle) = self.contact_factor.take() {
+                population.remove_contact_factor(handle);
+            }
+        }
+        self.tick += 1;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use structure::time::TimeUnit::Hours;
+
+    use crate::game::pathogen::symptoms::base::cheat::CustomCatchChance;
+    use crate::game::pathogen::symptoms::Symp;
+    use crate::game::pathogen::types::{PathogenType, Virus};
+    use crate::game::population::person_behavior::interaction::InteractionController;
+    use crate::game::population::person_behavior::lockdown::LockdownController;
+    use crate::game::population::person_behavior::Controller;
+    use crate::game::population::{PersonBuilder, Population, UniformDistribution};
+    use crate::game::Update;
+
+    /// Runs an epidemic, locking down after `lockdown_start` runs if given, and gets the peak
+    /// amount of simultaneous infections and the run it happened at
+    fn peak_infected(lockdown_start: Option<usize>) -> (usize, usize) {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            500,
+            UniformDistribution::new(20, 60),
+        );
+        pop.set_damage_interval(Hours(4));
+        let mut pathogen = Virus.create_pathogen("Test", 0);
+        pathogen.acquire_symptom(&CustomCatchChance(99.0).get_symptom(), None);
+        let pathogen = Arc::new(pathogen);
+        assert!(pop.infect_one(&pathogen));
+
+        let pop = Arc::new(Mutex::new(pop));
+        let mut interactions = InteractionController::new(&pop);
+        let mut lockdown =
+            lockdown_start.map(|start| LockdownController::new(&pop, 0.95, start, 1000));
+
+        let mut peak = (0, 0);
+        for run in 0..300 {
+            {
+                let mut guard = pop.lock().unwrap();
+                if guard.get_infected().is_empty() {
+                    break;
+                }
+                guard.update(20 * 60 * 4);
+                let infected = guard
+                    .get_infected()
+                    .iter()
+                    .filter(|p| p.read().unwrap().infected())
+                    .count();
+                if infected > peak.0 {
+                    peak = (infected, run);
+                }
+            }
+            if let Some(lockdown) = &mut lockdown {
+                lockdown.run();
+            }
+            interactions.run();
+        }
+        peak
+    }
+
+    #[test]
+    fn overlapping_lockdowns_end_cleanly() {
+        let pop = Arc::new(Mutex::new(Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            10,
+            UniformDistribution::new(20, 60),
+        )));
+        let mut first = LockdownController::new(&pop, 0.5, 0, 4);
+        let mut second = LockdownController::new(&pop, 0.5, 2, 4);
+
+        let mut scales = Vec::new();
+        for _ in 0..8 {
+            first.run();
+            second.run();
+            scales.push(pop.lock().unwrap().contact_scale());
+        }
+        assert_eq!(scales, [0.5, 0.5, 0.25, 0.25, 0.5, 0.5, 1.0, 1.0]);
+        assert!(!first.active() && !second.active());
+    }
+
+    #[test]
+    fn lockdown_flattens_the_curve() {
+        let (unrestricted, peak_run) = peak_infected(None);
+        let (locked_down, _) = peak_infected(Some(peak_run / 3));
+        assert!(
+            locked_down < unrestricted,
+            "Lockdown peaked at {}, without a lockdown peaked at {}",
+            locked_down,
+            unrestricted
+        );
+    }
+}
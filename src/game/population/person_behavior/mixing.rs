@@ -0,0 +1,65 @@
+use std::sync::Mutex;
+
+use crate::game::population::MAX_AGE;
+
+///
+/// Counts the contacts made between age groups, so that the mixing a contact model produces can
+/// be checked against the mixing it was meant to produce
+///
+/// Contacts are directed, `contacts()[a][b]` is the amount of contacts that infected people in
+/// group `a` made with people in group `b`. The recorder is shared between threads, so all
+/// recording is done through `&self`
+pub struct InteractionMatrix {
+    bin_size: usize,
+    contacts: Mutex<Vec<Vec<usize>>>,
+}
+
+impl InteractionMatrix {
+    /// Creates an empty matrix over age groups that are `bin_size` years wide, starting from 0.
+    /// Anyone older than [MAX_AGE] is counted in the last group.
+    ///
+    /// # Panics
+    /// Panics if `bin_size` is 0
+    pub fn new(bin_size: usize) -> Self {
+        if bin_size == 0 {
+            panic!("Invalid bin size: {}", bin_size);
+        }
+        let groups = MAX_AGE as usize / bin_size + 1;
+        Self {
+            bin_size,
+            contacts: Mutex::new(vec![vec![0; groups]; groups]),
+        }
+    }
+
+    /// How many years wide every age group is
+    pub fn bin_size(&self) -> usize {
+        self.bin_size
+    }
+
+    /// The amount of age groups along each side of the matrix
+    pub fn groups(&self) -> usize {
+        MAX_AGE as usize / self.bin_size + 1
+    }
+
+    /// Gets the age group that someone who is `age` years old belongs to
+    pub fn group_of(&self, age: usize) -> usize {
+        usize::min(age / self.bin_size, self.groups() - 1)
+    }
+
+    /// Records a contact from someone `from_age` years old with someone `to_age` years old
+    pub fn record(&self, from_age: usize, to_age: usize) {
+        let (from, to) = (self.group_of(from_age), self.group_of(to_age));
+        self.contacts.lock().unwrap()[from][to] += 1;
+    }
+
+    /// Gets the recorded contacts, indexed by the age group of the infected person and then the
+    /// age group of who they contacted
+    pub fn contacts(&self) -> Vec<Vec<usize>> {
+        self.contacts.lock().unwrap().clone()
+    }
+
+    /// The total amount of recorded contacts
+    pub fn total(&self) -> usize {
+        self.contacts.lock().unwrap().iter().flatten().sum()
+    }
+}
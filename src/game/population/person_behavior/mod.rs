@@ -1,7 +1,13 @@
 use std::time::Duration;
 
 pub mod interaction;
+pub mod lockdown;
+pub mod mixing;
+pub mod quarantine;
+pub mod tracing;
+pub mod transmission;
 pub mod travel;
+pub mod vaccination;
 
 pub trait Controller {
     fn run(&mut self);
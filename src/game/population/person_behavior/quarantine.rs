@@ -0,0 +1,171 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use crate::game::population::person_behavior::Controller;
+use crate::game::population::Population;
+use crate::game::roll;
+
+///
+/// Looks for infected people and quarantines them, so they stop making contacts. Every run, each
+/// infected person who isn't quarantined yet is detected with their detection chance, and anyone
+/// quarantined who is no longer infected is released.
+pub struct QuarantineController {
+    population: Arc<Mutex<Population>>,
+    quarantined: HashSet<usize>, // ids of the people this controller has quarantined
+    detected: usize,
+}
+
+impl QuarantineController {
+    pub fn new(population: &Arc<Mutex<Population>>) -> Self {
+        Self {
+            population: population.clone(),
+            quarantined: HashSet::new(),
+            detected: 0,
+        }
+    }
+
+    /// The total amount of people that have been detected and quarantined
+    pub fn detected(&self) -> usize {
+        self.detected
+    }
+}
+
+impl Controller for QuarantineController {
+    fn run(&mut self) {
+        let population = self
+            .population
+            .lock()
+            .expect("Should have been able to receive population");
+
+        // people who have recovered are dropped from the infected, so release from everyone
+        if !self.quarantined.is_empty() {
+            for person in population.get_everyone() {
+                let mut person = person.write().unwrap();
+                if self.quarantined.contains(&person.id()) && !person.infected() {
+                    person.set_quarantined(false);
+                    self.quarantined.remove(&person.id());
+                }
+            }
+        }
+
+        for person in population.get_infected() {
+            let mut person = person.write().unwrap();
+            if !person.quarantined() && roll(person.detection_chance()) {
+                person.set_quarantined(true);
+                self.quarantined.insert(person.id());
+                self.detected += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use crate::game::pathogen::symptoms::Symptom;
+    use crate::game::pathogen::types::{PathogenType, Virus};
+    use crate::game::pathogen::Pathogen;
+    use crate::game::population::person_behavior::quarantine::QuarantineController;
+    use crate::game::population::person_behavior::Controller;
+    use crate::game::population::{PersonBuilder, Population, UniformDistribution};
+
+    fn detectable_pathogen(detectability: f64) -> Arc<Pathogen> {
+        let mut pathogen = Virus.create_pathogen("Test", 0);
+        let symptom = Symptom::new(
+            "Test".to_string(),
+            "Test".to_string(),
+            1.0,
+            10.0,
+            1.0,
+            1.0,
+            None,
+            None,
+            None,
+            None,
+        )
+        .with_detectability(detectability);
+        pathogen.acquire_symptom(&symptom, None);
+        Arc::new(pathogen)
+    }
+
+    fn quarantined_count(pop: &Arc<Mutex<Population>>) -> usize {
+        pop.lock()
+            .unwrap()
+            .get_everyone()
+            .iter()
+            .filter(|p| p.read().unwrap().quarantined())
+            .count()
+    }
+
+    /// Gets how many of 100 active cases are quarantined after a single run, when the strain
+    /// has a symptom with `detectability`
+    fn quarantined_with(detectability: f64) -> usize {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            200,
+            UniformDistribution::new(20, 60),
+        );
+        let pathogen = detectable_pathogen(detectability);
+        for id in 0..100 {
+            let person = pop.get_everyone()[id].clone();
+            assert!(person.write().unwrap().infect_with_dose(&pathogen, 30_000_000));
+        }
+        pop.rebuild_infected();
+
+        let pop = Arc::new(Mutex::new(pop));
+        let mut controller = QuarantineController::new(&pop);
+        controller.run();
+        let quarantined = quarantined_count(&pop);
+        assert_eq!(quarantined, controller.detected());
+        quarantined
+    }
+
+    #[test]
+    fn noticeable_symptoms_are_quarantined_more() {
+        let stealthy = quarantined_with(0.0);
+        let coughing = quarantined_with(0.8);
+        assert!(
+            stealthy < coughing,
+            "Quarantined {} stealthy cases, {} coughing cases",
+            stealthy,
+            coughing
+        );
+    }
+
+    #[test]
+    fn recovered_cases_are_released() {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            100,
+            UniformDistribution::new(20, 60),
+        );
+        let pathogen = detectable_pathogen(1.0);
+        for id in 0..20 {
+            let person = pop.get_everyone()[id].clone();
+            assert!(person.write().unwrap().infect_with_dose(&pathogen, 30_000_000));
+        }
+        pop.rebuild_infected();
+
+        let pop = Arc::new(Mutex::new(pop));
+        let mut controller = QuarantineController::new(&pop);
+        controller.run();
+        assert_eq!(quarantined_count(&pop), 20);
+
+        {
+            let mut guard = pop.lock().unwrap();
+            for person in guard.get_everyone().iter().take(10) {
+                assert!(person.write().unwrap().cure(pathogen.name()));
+            }
+            guard.cleanup();
+            assert_eq!(guard.get_infected().len(), 10);
+        }
+        controller.run();
+        assert_eq!(quarantined_count(&pop), 10);
+        assert!(pop.lock().unwrap().get_everyone()[..10]
+            .iter()
+            .all(|p| !p.read().unwrap().quarantined()));
+    }
+}
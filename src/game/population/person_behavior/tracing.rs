@@ -0,0 +1,392 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::{Arc, Mutex};
+
+use structure::time::TimeUnit::Days;
+use structure::time::{Time, TimeUnit};
+
+use crate::game::population::person_behavior::Controller;
+use crate::game::population::Population;
+use crate::game::roll;
+
+/// The default amount of game time that contacts are remembered for
+pub const DEFAULT_TRACING_WINDOW: TimeUnit = Days(7);
+/// The default amount of game time that traced contacts stay in quarantine for
+pub const DEFAULT_TRACED_QUARANTINE: TimeUnit = Days(14);
+
+///
+/// Remembers who everyone has been in contact with recently, so that the contacts of someone who
+/// is found to be infected can be traced. Contacts older than the log's window are forgotten.
+///
+/// The log is shared between threads, so all recording is done through `&self`
+pub struct ContactLog {
+    window: usize, // in minutes
+    minute: AtomicUsize,
+    contacts: Mutex<HashMap<usize, VecDeque<(usize, usize)>>>, // contacts and when they happened
+}
+
+impl ContactLog {
+    /// Creates an empty log that remembers contacts for `window` of game time
+    ///
+    /// # Panics
+    /// Panics if `window` is shorter than a minute
+    pub fn new(window: TimeUnit) -> Self {
+        let window = usize::from(window.into_minutes());
+        if window == 0 {
+            panic!("Invalid tracing window: {} minutes", window);
+        }
+        Self {
+            window,
+            minute: AtomicUsize::new(0),
+            contacts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// How long contacts are remembered for
+    pub fn window(&self) -> TimeUnit {
+        TimeUnit::Minutes(self.window)
+    }
+
+    /// The game minute that newly recorded contacts will be tagged with
+    pub fn current_minute(&self) -> usize {
+        self.minute.load(SeqCst)
+    }
+
+    /// Moves the log's clock forward by `minutes`, forgetting every contact that falls out of
+    /// the window
+    pub fn advance(&self, minutes: usize) {
+        let now = self.minute.fetch_add(minutes, SeqCst) + minutes;
+        let oldest = now.saturating_sub(self.window);
+        let mut contacts = self.contacts.lock().unwrap();
+        for log in contacts.values_mut() {
+            while log.front().is_some_and(|&(_, minute)| minute < oldest) {
+                log.pop_front();
+            }
+        }
+        contacts.retain(|_, log| !log.is_empty());
+    }
+
+    /// Records a contact between the people with the ids `a` and `b`, which both of them
+    /// remember
+    pub fn record(&self, a: usize, b: usize) {
+        let minute = self.current_minute();
+        let mut contacts = self.contacts.lock().unwrap();
+        contacts.entry(a).or_default().push_back((b, minute));
+        contacts.entry(b).or_default().push_back((a, minute));
+    }
+
+    /// Gets everyone that `id` has been in contact with within the window, in the order of their
+    /// first contact
+    pub fn recent_contacts(&self, id: usize) -> Vec<usize> {
+        let mut output = Vec::new();
+        if let Some(log) = self.contacts.lock().unwrap().get(&id) {
+            for &(contact, _) in log {
+                if !output.contains(&contact) {
+                    output.push(contact);
+                }
+            }
+        }
+        output
+    }
+}
+
+///
+/// Runs a test, trace and isolate program. Infected people are detected like by a
+/// [QuarantineController](crate::game::population::person_behavior::quarantine::QuarantineController),
+/// and every contact of a detected person in the [ContactLog] is found with a chance equal to
+/// the tracing efficiency and quarantined as well. Detected people are released once they're no
+/// longer infected, and traced contacts once their quarantine is over, unless they turned out to
+/// be infected.
+///
+/// The log has to be filled by an
+/// [InteractionController](crate::game::population::person_behavior::interaction::InteractionController)
+/// through `log_contacts`, which also keeps its clock.
+pub struct ContactTracingController {
+    population: Arc<Mutex<Population>>,
+    log: Arc<ContactLog>,
+    efficiency: f64,
+    quarantine_period: usize, // in minutes
+    in_quarantine: Vec<(usize, usize)>, // quarantined people and the minute they can be released
+    detected: usize,
+    traced: usize,
+}
+
+impl ContactTracingController {
+    /// Creates a program that finds each contact of a detected person with a chance of
+    /// `efficiency`
+    ///
+    /// # Panics
+    /// Panics if `efficiency` is not within the range [0, 1]
+    pub fn new(
+        population: &Arc<Mutex<Population>>,
+        log: &Arc<ContactLog>,
+        efficiency: f64,
+    ) -> Self {
+        if !(0.0..=1.0).contains(&efficiency) {
+            panic!("Invalid tracing efficiency: {}", efficiency);
+        }
+        Self {
+            population: population.clone(),
+            log: log.clone(),
+            efficiency,
+            quarantine_period: usize::from(DEFAULT_TRACED_QUARANTINE.into_minutes()),
+            in_quarantine: Vec::new(),
+            detected: 0,
+            traced: 0,
+        }
+    }
+
+    /// The chance that each contact of a detected person is found
+    pub fn efficiency(&self) -> f64 {
+        self.efficiency
+    }
+
+    /// How long traced contacts stay in quarantine
+    pub fn quarantine_period(&self) -> TimeUnit {
+        TimeUnit::Minutes(self.quarantine_period)
+    }
+
+    /// Sets how long traced contacts stay in quarantine
+    pub fn set_quarantine_period(&mut self, period: TimeUnit) {
+        self.quarantine_period = usize::from(period.into_minutes());
+    }
+
+    /// The total amount of infected people that have been detected and quarantined
+    pub fn detected(&self) -> usize {
+        self.detected
+    }
+
+    /// The total amount of contacts that have been traced and quarantined
+    pub fn traced(&self) -> usize {
+        self.traced
+    }
+}
+
+impl Controller for ContactTracingController {
+    fn run(&mut self) {
+        let population = self
+            .population
+            .lock()
+            .expect("Should have been able to receive population");
+        let now = self.log.current_minute();
+
+        let mut newly_detected = Vec::new();
+        for person in population.get_infected() {
+            let mut person = person.write().unwrap();
+            if !person.quarantined() && roll(person.detection_chance()) {
+                person.set_quarantined(true);
+                newly_detected.push(person.id());
+                self.in_quarantine.push((person.id(), now));
+                self.detected += 1;
+            }
+        }
+
+        let mut found = HashSet::new();
+        for id in newly_detected {
+            for contact in self.log.recent_contacts(id) {
+                if !found.contains(&contact) && roll(self.efficiency) {
+                    found.insert(contact);
+                }
+            }
+        }
+
+        // recovered people are dropped from the infected, so everyone has to be checked
+        let release_at = now + self.quarantine_period;
+        let due = self
+            .in_quarantine
+            .iter()
+            .filter(|&&(_, release)| release <= now)
+            .map(|&(id, _)| id)
+            .collect::<HashSet<_>>();
+        let mut held = HashSet::new();
+        for person in population.get_everyone() {
+            let mut person = person.write().unwrap();
+            let id = person.id();
+            if found.contains(&id) && !person.quarantined() {
+                person.set_quarantined(true);
+                self.in_quarantine.push((id, release_at));
+                self.traced += 1;
+            } else if due.contains(&id) {
+                if person.infected() {
+                    held.insert(id);
+                } else {
+                    person.set_quarantined(false);
+                }
+            }
+        }
+        self.in_quarantine
+            .retain(|&(id, release)| release > now || held.contains(&id));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+    use std::sync::{Arc, Mutex};
+
+    use structure::graph::Graph;
+    use structure::time::TimeUnit::{Days, Hours};
+
+    use crate::game::pathogen::symptoms::Symptom;
+    use crate::game::pathogen::Pathogen;
+    use crate::game::population::person_behavior::interaction::InteractionController;
+    use crate::game::population::person_behavior::quarantine::QuarantineController;
+    use crate::game::population::person_behavior::tracing::{
+        ContactLog, ContactTracingController,
+    };
+    use crate::game::population::person_behavior::Controller;
+    use crate::game::population::{
+        MortalityModel, PersonBuilder, Population, UniformDistribution,
+    };
+    use crate::game::Update;
+
+    #[test]
+    fn old_contacts_are_forgotten() {
+        let log = ContactLog::new(Days(1));
+        log.record(0, 1);
+        log.advance(12 * 60);
+        log.record(0, 2);
+        assert_eq!(log.recent_contacts(0), vec![1, 2]);
+        assert_eq!(log.recent_contacts(2), vec![0]);
+
+        log.advance(18 * 60);
+        assert_eq!(log.recent_contacts(0), vec![2]);
+        assert!(log.recent_contacts(1).is_empty());
+    }
+
+    /// Runs an outbreak to the end, detecting cases every hour, and gets how many people were
+    /// ever infected. Contacts are traced with `efficiency` if it's set, otherwise only the
+    /// detected are quarantined.
+    fn total_infections(efficiency: Option<f64>, seed: u64) -> usize {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            500,
+            UniformDistribution::new(20, 60),
+        );
+        pop.set_mortality_model(MortalityModel::CaseFatality(0.0));
+        let mut pathogen = Pathogen::new(
+            "Test".to_string(),
+            0,
+            0.0,
+            2 * 24 * 60,
+            0,
+            Graph::new(),
+            HashSet::new(),
+        );
+        let symptom = Symptom::new(
+            "Test".to_string(),
+            "Test".to_string(),
+            0.15,
+            0.0,
+            0.0,
+            0.0,
+            None,
+            None,
+            None,
+            None,
+        )
+        .with_detectability(0.03);
+        pathogen.acquire_symptom(&symptom, None);
+        let pathogen = Arc::new(pathogen);
+        for person in pop.get_everyone().iter().take(5) {
+            assert!(person.write().unwrap().infect_with_dose(&pathogen, 30_000_000));
+        }
+        pop.rebuild_infected();
+
+        let pop = Arc::new(Mutex::new(pop));
+        let log = Arc::new(ContactLog::new(Days(7)));
+        let mut interactions = InteractionController::new(&pop);
+        interactions.set_seed(seed);
+        interactions.set_time_step(Hours(1));
+        interactions.log_contacts(&log);
+        let mut detection: Box<dyn Controller> = match efficiency {
+            Some(efficiency) => Box::new(ContactTracingController::new(&pop, &log, efficiency)),
+            None => Box::new(QuarantineController::new(&pop)),
+        };
+        while !pop.lock().unwrap().get_infected().is_empty() {
+            interactions.run();
+            detection.run();
+            pop.lock().unwrap().update(20 * 60);
+        }
+        let pop = pop.lock().unwrap();
+        pop.get_all_ever_infected()
+    }
+
+    #[test]
+    fn tracing_beats_quarantine_alone() {
+        let seeds = [2187, 2188, 2189];
+        let quarantined: usize = seeds.iter().map(|&s| total_infections(None, s)).sum();
+        let traced: usize = seeds.iter().map(|&s| total_infections(Some(1.0), s)).sum();
+        assert!(
+            traced < quarantined,
+            "{} infected with tracing, {} with quarantine alone",
+            traced,
+            quarantined
+        );
+    }
+
+    #[test]
+    fn detected_cases_are_released_after_recovering() {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            100,
+            UniformDistribution::new(20, 60),
+        );
+        let mut pathogen = Pathogen::new(
+            "Test".to_string(),
+            0,
+            0.0,
+            2 * 24 * 60,
+            0,
+            Graph::new(),
+            HashSet::new(),
+        );
+        let symptom = Symptom::new(
+            "Test".to_string(),
+            "Test".to_string(),
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            None,
+            None,
+            None,
+            None,
+        )
+        .with_detectability(1.0);
+        pathogen.acquire_symptom(&symptom, None);
+        let pathogen = Arc::new(pathogen);
+        for person in pop.get_everyone().iter().take(10) {
+            assert!(person.write().unwrap().infect_with_dose(&pathogen, 30_000_000));
+        }
+        pop.rebuild_infected();
+
+        let pop = Arc::new(Mutex::new(pop));
+        let log = Arc::new(ContactLog::new(Days(7)));
+        let mut controller = ContactTracingController::new(&pop, &log, 0.0);
+        let quarantined = |pop: &Arc<Mutex<Population>>| {
+            pop.lock()
+                .unwrap()
+                .get_everyone()
+                .iter()
+                .filter(|p| p.read().unwrap().quarantined())
+                .count()
+        };
+        controller.run();
+        assert_eq!(quarantined(&pop), 10);
+
+        {
+            let mut guard = pop.lock().unwrap();
+            for person in guard.get_everyone().iter().take(10) {
+                assert!(person.write().unwrap().cure("Test"));
+            }
+            guard.cleanup();
+            assert!(guard.get_infected().is_empty());
+        }
+        controller.run();
+        assert_eq!(quarantined(&pop), 0);
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::Mutex;
+
+use structure::time::TimeUnit;
+use structure::time::TimeUnit::Minutes;
+
+/// A single recorded infection event
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Transmission {
+    pub infector: usize,
+    pub infectee: usize,
+    pub tick: usize,
+}
+
+///
+/// Records who infected whom during a run, so that the outbreak tree can be rebuilt afterwards
+///
+/// The recorder is shared between threads, so all recording is done through `&self`
+pub struct TransmissionTree {
+    transmissions: Mutex<Vec<Transmission>>,
+    tick: AtomicUsize,
+}
+
+impl TransmissionTree {
+    pub fn new() -> Self {
+        Self {
+            transmissions: Mutex::new(Vec::new()),
+            tick: AtomicUsize::new(0),
+        }
+    }
+
+    /// The tick that newly recorded transmissions will be tagged with
+    pub fn current_tick(&self) -> usize {
+        self.tick.load(SeqCst)
+    }
+
+    /// Moves the recorder's clock forward
+    pub fn advance(&self, ticks: usize) {
+        self.tick.fetch_add(ticks, SeqCst);
+    }
+
+    pub fn record(&self, infector: usize, infectee: usize) {
+        let tick = self.current_tick();
+        self.transmissions.lock().unwrap().push(Transmission {
+            infector,
+            infectee,
+            tick,
+        });
+    }
+
+    /// Gets every recorded transmission in the order they were recorded
+    pub fn transmissions(&self) -> Vec<Transmission> {
+        self.transmissions.lock().unwrap().clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.transmissions.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Gets the person who infected `infectee`, if the infection was recorded
+    pub fn infector_of(&self, infectee: usize) -> Option<usize> {
+        self.transmissions
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|t| t.infectee == infectee)
+            .map(|t| t.infector)
+    }
+
+    /// Gets everyone that `infector` directly infected
+    pub fn infected_by(&self, infector: usize) -> Vec<usize> {
+        self.transmissions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|t| t.infector == infector)
+            .map(|t| t.infectee)
+            .collect()
+    }
+
+    /// Gets the time between each infector catching their infection and passing it on, for every
+    /// transmission whose infector's own infection was recorded, in the order they were recorded.
+    /// Every recorder tick is counted as a game minute.
+    pub fn generation_intervals(&self) -> Vec<TimeUnit> {
+        let mut infected_at: HashMap<usize, usize> = HashMap::new();
+        let mut output = Vec::new();
+        for t in &*self.transmissions.lock().unwrap() {
+            if let Some(&start) = infected_at.get(&t.infector) {
+                output.push(Minutes(t.tick.saturating_sub(start)));
+            }
+            infected_at.insert(t.infectee, t.tick);
+        }
+        output
+    }
+
+    /// Gets the people who spread the infection without catching it from anyone recorded,
+    /// which for a full run are the seeded cases
+    pub fn roots(&self) -> Vec<usize> {
+        let transmissions = self.transmissions.lock().unwrap();
+        let infectees: HashSet<usize> = transmissions.iter().map(|t| t.infectee).collect();
+        let mut output = Vec::new();
+        for t in &*transmissions {
+            if !infectees.contains(&t.infector) && !output.contains(&t.infector) {
+                output.push(t.infector);
+            }
+        }
+        output
+    }
+
+    /// Maps every infector to the people they infected
+    pub fn children(&self) -> HashMap<usize, Vec<usize>> {
+        let mut output: HashMap<usize, Vec<usize>> = HashMap::new();
+        for t in &*self.transmissions.lock().unwrap() {
+            output.entry(t.infector).or_default().push(t.infectee);
+        }
+        output
+    }
+}
+
+impl Default for TransmissionTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
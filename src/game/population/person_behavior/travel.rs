@@ -1 +1,110 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
+use crate::game::board::GameBoard;
+use crate::game::population::person_behavior::Controller;
+use crate::game::population::Population;
+use crate::game::roll;
+
+/// Moves people between the chunks of a board, using the board's gravity model to decide
+/// how many people travel along each connection
+pub struct TravelController {
+    board: Arc<Mutex<GameBoard>>,
+    populations: HashMap<usize, Arc<Mutex<Population>>>,
+    travelers: HashMap<(usize, usize), usize>,
+}
+
+impl TravelController {
+    pub fn new(board: &Arc<Mutex<GameBoard>>) -> Self {
+        Self {
+            board: board.clone(),
+            populations: HashMap::new(),
+            travelers: HashMap::new(),
+        }
+    }
+
+    /// Links the people living in a chunk to the controller, so that their behavioral activity
+    /// suppresses how many of them travel
+    pub fn track_population(&mut self, chunk: usize, population: &Arc<Mutex<Population>>) {
+        self.populations.insert(chunk, population.clone());
+    }
+
+    fn activity(&self, chunk: usize) -> f64 {
+        match self.populations.get(&chunk) {
+            None => 1.0,
+            Some(population) => population
+                .lock()
+                .expect("Should have been able to receive population")
+                .average_behavioral_activity(),
+        }
+    }
+
+    /// The total amount of people that have traveled from chunk `from` to chunk `to`
+    pub fn travelers(&self, from: usize, to: usize) -> usize {
+        *self.travelers.get(&(from, to)).unwrap_or(&0)
+    }
+}
+
+impl Controller for TravelController {
+    fn run(&mut self) {
+        let mut board = self
+            .board
+            .lock()
+            .expect("Should have been able to receive board");
+
+        let mut relocations = Vec::new();
+        for (from, to) in board.chunk_graph().edges() {
+            let expected = board.travel_probability(*from, *to) * self.activity(*from);
+            let mut count = expected.floor() as usize;
+            if roll(expected.fract()) {
+                count += 1;
+            }
+            relocations.push((*from, *to, count));
+        }
+
+        for (from, to, count) in relocations {
+            let moved = board.relocate(from, to, count);
+            *self.travelers.entry((from, to)).or_insert(0) += moved;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use crate::game::board::{Adjacency, Chunk, GameBoard};
+    use crate::game::population::person_behavior::travel::TravelController;
+    use crate::game::population::person_behavior::Controller;
+
+    #[test]
+    fn large_populations_exchange_more_travelers() {
+        let mut board = GameBoard::new();
+        board.add_chunk(0, Chunk::new(10_000, 1.0)).unwrap();
+        board.add_chunk(1, Chunk::new(10_000, 1.0)).unwrap();
+        board.add_chunk(2, Chunk::new(100, 1.0)).unwrap();
+        board.connect(0, 1, Adjacency::Land(1.0)).unwrap();
+        board.connect(1, 2, Adjacency::Land(1.0)).unwrap();
+
+        assert!(board.travel_probability(1, 0) > board.travel_probability(1, 2));
+        assert!(board.travel_probability(0, 1) > board.travel_probability(2, 1));
+        // both chunks of a pair count the same, no matter which one is traveled from
+        assert_eq!(board.travel_probability(1, 2), board.travel_probability(2, 1));
+        assert_eq!(board.travel_probability(0, 2), 0.0);
+
+        let board = Arc::new(Mutex::new(board));
+        let mut controller = TravelController::new(&board);
+        for _ in 0..50 {
+            controller.run();
+        }
+
+        let large_exchange = controller.travelers(0, 1) + controller.travelers(1, 0);
+        let small_exchange = controller.travelers(1, 2) + controller.travelers(2, 1);
+        assert!(
+            large_exchange > small_exchange,
+            "Large pair exchanged {}, small pair exchanged {}",
+            large_exchange,
+            small_exchange
+        );
+    }
+}
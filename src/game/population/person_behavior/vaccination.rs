@@ -0,0 +1,237 @@
+use std::sync::{Arc, Mutex};
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{thread_rng, SeedableRng};
+
+use crate::game::pathogen::Pathogen;
+use crate::game::population::person_behavior::Controller;
+use crate::game::population::{Compartment, Population};
+
+/// How many people a vaccination campaign reaches every run
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum RolloutRate {
+    /// A fixed amount of people every run
+    Doses(usize),
+    /// A fraction of the people who are still susceptible and unvaccinated every run
+    Fraction(f64),
+}
+
+///
+/// Rolls out a vaccine over time, vaccinating randomly chosen susceptible people who aren't
+/// vaccinated yet every run until the campaign has covered its goal fraction of the original
+/// population
+///
+/// Each call to `run` counts as one tick of the rollout
+pub struct VaccinationController {
+    population: Arc<Mutex<Population>>,
+    pathogen: Arc<Pathogen>,
+    rate: RolloutRate,
+    coverage: f64,
+    vaccinated: usize,
+    rng: Option<StdRng>, // when set, the same people are chosen every time
+}
+
+impl VaccinationController {
+    /// Creates a campaign that vaccinates against `pathogen` at `rate` until `coverage` of the
+    /// original population has been vaccinated
+    ///
+    /// # Panics
+    /// Panics if `coverage` or a fractional `rate` is not within the range [0, 1]
+    pub fn new(
+        population: &Arc<Mutex<Population>>,
+        pathogen: &Arc<Pathogen>,
+        rate: RolloutRate,
+        coverage: f64,
+    ) -> Self {
+        if let RolloutRate::Fraction(fraction) = rate {
+            if !(0.0..=1.0).contains(&fraction) {
+                panic!("Invalid rollout fraction: {}", fraction);
+            }
+        }
+        if !(0.0..=1.0).contains(&coverage) {
+            panic!("Invalid coverage: {}", coverage);
+        }
+        Self {
+            population: population.clone(),
+            pathogen: pathogen.clone(),
+            rate,
+            coverage,
+            vaccinated: 0,
+            rng: None,
+        }
+    }
+
+    /// Chooses who gets vaccinated with a rng seeded with `seed`, so that the same campaign
+    /// always reaches the same people
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = Some(StdRng::seed_from_u64(seed));
+    }
+
+    /// The total amount of people that have been vaccinated
+    pub fn vaccinated(&self) -> usize {
+        self.vaccinated
+    }
+
+    /// Whether the campaign has reached its coverage goal
+    pub fn finished(&self) -> bool {
+        self.vaccinated >= self.goal()
+    }
+
+    fn goal(&self) -> usize {
+        let population = self
+            .population
+            .lock()
+            .expect("Should have been able to receive population");
+        (population.get_original_population() as f64 * self.coverage).round() as usize
+    }
+}
+
+impl Controller for VaccinationController {
+    fn run(&mut self) {
+        let remaining = self.goal().saturating_sub(self.vaccinated);
+        if remaining == 0 {
+            return;
+        }
+        let population = self
+            .population
+            .lock()
+            .expect("Should have been able to receive population");
+
+        let susceptible = population
+            .get_everyone()
+            .iter()
+            .filter(|p| {
+                let person = p.read().unwrap();
+                person.compartment() == Compartment::Susceptible
+                    && !person.vaccinated_against(self.pathogen.name())
+            })
+            .collect::<Vec<_>>();
+        let doses = match self.rate {
+            RolloutRate::Doses(doses) => doses,
+            RolloutRate::Fraction(fraction) => {
+                (susceptible.len() as f64 * fraction).round() as usize
+            }
+        };
+
+        let chosen = match &mut self.rng {
+            Some(rng) => susceptible.choose_multiple(rng, doses.min(remaining)),
+            None => susceptible.choose_multiple(&mut thread_rng(), doses.min(remaining)),
+        };
+        for person in chosen {
+            if person.write().unwrap().vaccinate(&self.pathogen) {
+                self.vaccinated += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use structure::time::TimeUnit::Hours;
+
+    use crate::game::pathogen::symptoms::base::cheat::CustomCatchChance;
+    use crate::game::pathogen::symptoms::Symp;
+    use crate::game::pathogen::types::{PathogenType, Virus};
+    use crate::game::population::person_behavior::interaction::InteractionController;
+    use crate::game::population::person_behavior::vaccination::{
+        RolloutRate, VaccinationController,
+    };
+    use crate::game::population::person_behavior::Controller;
+    use crate::game::population::{PersonBuilder, Population, UniformDistribution};
+    use crate::game::Update;
+
+    /// Runs an epidemic, rolling out a vaccine from `rollout_start` runs on if given, and gets the
+    /// peak amount of simultaneous infections and the run it happened at
+    fn peak_infected(rollout_start: Option<usize>) -> (usize, usize) {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            500,
+            UniformDistribution::new(20, 60),
+        );
+        pop.set_damage_interval(Hours(4));
+        pop.set_seed(2191);
+        let mut pathogen = Virus.create_pathogen("Test", 0);
+        pathogen.acquire_symptom(&CustomCatchChance(99.0).get_symptom(), None);
+        let pathogen = Arc::new(pathogen);
+        assert!(pop.infect_person(0, &pathogen));
+
+        let pop = Arc::new(Mutex::new(pop));
+        let mut interactions = InteractionController::new(&pop);
+        interactions.set_seed(2191);
+        let mut rollout =
+            VaccinationController::new(&pop, &pathogen, RolloutRate::Fraction(0.2), 0.9);
+        rollout.set_seed(2191);
+
+        let mut peak = (0, 0);
+        for run in 0..300 {
+            {
+                let mut guard = pop.lock().unwrap();
+                if guard.get_infected().is_empty() {
+                    break;
+                }
+                guard.update(20 * 60 * 4);
+                let infected = guard
+                    .get_infected()
+                    .iter()
+                    .filter(|p| p.read().unwrap().infected())
+                    .count();
+                if infected > peak.0 {
+                    peak = (infected, run);
+                }
+            }
+            if rollout_start.is_some_and(|start| run >= start) {
+                rollout.run();
+            }
+            interactions.run();
+        }
+        peak
+    }
+
+    #[test]
+    fn early_rollout_prevents_the_peak() {
+        let (_, peak_run) = peak_infected(None);
+        let (early, _) = peak_infected(Some(0));
+        let (late, _) = peak_infected(Some(peak_run));
+        assert!(
+            early < late,
+            "Early rollout peaked at {}, late rollout peaked at {}",
+            early,
+            late
+        );
+    }
+
+    #[test]
+    fn rollout_stops_at_coverage() {
+        let pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            100,
+            UniformDistribution::new(20, 60),
+        );
+        let pathogen = Arc::new(Virus.create_pathogen("Test", 0));
+        let pop = Arc::new(Mutex::new(pop));
+        let mut rollout = VaccinationController::new(&pop, &pathogen, RolloutRate::Doses(7), 0.5);
+
+        rollout.run();
+        assert_eq!(rollout.vaccinated(), 7);
+        while !rollout.finished() {
+            rollout.run();
+        }
+        rollout.run();
+        assert_eq!(rollout.vaccinated(), 50);
+        let pop = pop.lock().unwrap();
+        let vaccinated = pop
+            .get_everyone()
+            .iter()
+            .filter(|p| p.read().unwrap().vaccinated_against(pathogen.name()))
+            .count();
+        assert_eq!(vaccinated, 50);
+        // vaccinations don't count as infections
+        assert_eq!(pop.compartments().recovered, 0);
+        assert_eq!(pop.get_all_ever_infected(), 0);
+    }
+}
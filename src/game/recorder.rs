@@ -0,0 +1,202 @@
+use structure::time::{Time, TimeUnit};
+use structure::time::TimeUnit::Minutes;
+
+use crate::game::population::{CompartmentCounts, Population};
+
+/// A snapshot of a population's compartments at some point in game time
+#[derive(Debug, Clone)]
+pub struct EpidemicSnapshot {
+    pub time: TimeUnit,
+    pub compartments: CompartmentCounts,
+}
+
+impl EpidemicSnapshot {
+    /// The amount of active cases, both exposed and infectious
+    pub fn infected(&self) -> usize {
+        self.compartments.exposed + self.compartments.infectious
+    }
+
+    /// The cumulative amount of cases, everyone who has been infected at some point
+    pub fn ever_infected(&self) -> usize {
+        self.infected() + self.compartments.recovered + self.compartments.dead
+    }
+}
+
+/// Records the course of an epidemic as a time series of compartment counts
+#[derive(Debug, Clone, Default)]
+pub struct EpidemicRecorder {
+    series: Vec<EpidemicSnapshot>,
+}
+
+impl EpidemicRecorder {
+    pub fn new() -> Self {
+        Self { series: Vec::new() }
+    }
+
+    pub fn record(&mut self, time: TimeUnit, population: &Population) {
+        self.series.push(EpidemicSnapshot {
+            time,
+            compartments: population.compartments(),
+        });
+    }
+
+    pub fn series(&self) -> &Vec<EpidemicSnapshot> {
+        &self.series
+    }
+
+    /// Gets the amount of active cases at every recorded time
+    pub fn infected_series(&self) -> Vec<(TimeUnit, usize)> {
+        self.series
+            .iter()
+            .map(|s| (s.time.clone(), s.infected()))
+            .collect()
+    }
+
+    /// Gets the time and size of the largest amount of simultaneous active cases. If the peak
+    /// was reached more than once, the first time is reported.
+    pub fn peak_infected(&self) -> (TimeUnit, usize) {
+        let mut output = (Minutes(0), 0);
+        for snapshot in &self.series {
+            if snapshot.infected() > output.1 {
+                output = (snapshot.time.clone(), snapshot.infected());
+            }
+        }
+        output
+    }
+
+    /// The first recorded time at which someone had been infected
+    fn first_case(&self) -> Option<&TimeUnit> {
+        self.series
+            .iter()
+            .find(|s| s.ever_infected() > 0)
+            .map(|s| &s.time)
+    }
+
+    /// How long it took from the first recorded case until the peak of active cases
+    ///
+    /// ###Return
+    /// The time to the peak, or `None` if nobody was ever infected or no snapshot had any
+    /// active cases
+    pub fn time_to_peak(&self) -> Option<TimeUnit> {
+        let first = usize::from(self.first_case()?.as_minutes());
+        let (peak, count) = self.peak_infected();
+        if count == 0 {
+            return None;
+        }
+        Some(Minutes(usize::from(peak.as_minutes()) - first))
+    }
+
+    /// How long the epidemic lasted, from the first recorded case until the first time
+    /// afterwards that there were no active cases left
+    ///
+    /// ###Return
+    /// The duration, or `None` if nobody was ever infected or the epidemic was still ongoing
+    /// when the recording ended
+    pub fn epidemic_duration(&self) -> Option<TimeUnit> {
+        let first = usize::from(self.first_case()?.as_minutes());
+        let end = self
+            .series
+            .iter()
+            .find(|s| s.ever_infected() > 0 && s.infected() == 0)?;
+        Some(Minutes(usize::from(end.time.as_minutes()) - first))
+    }
+
+    /// Estimates how long the cumulative amount of cases took to double while the epidemic was
+    /// growing exponentially. An exponential is fitted to the cumulative cases recorded from the
+    /// first case up to the peak of active cases, where growth starts to slow down.
+    ///
+    /// ###Return
+    /// The doubling time, or `None` if fewer than two points were recorded in the growth phase or
+    /// the cases didn't grow
+    pub fn doubling_time(&self) -> Option<TimeUnit> {
+        let peak = self.peak_infected().0;
+        let points = self
+            .series
+            .iter()
+            .filter(|s| s.ever_infected() > 0 && s.time <= peak)
+            .map(|s| {
+                let minutes = usize::from(s.time.as_minutes()) as f64;
+                (minutes, (s.ever_infected() as f64).ln())
+            })
+            .collect::<Vec<_>>();
+        if points.len() < 2 {
+            return None;
+        }
+
+        // least squares fit of ln(cases) = intercept + slope * minutes
+        let n = points.len() as f64;
+        let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+        let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+        let covariance = points
+            .iter()
+            .map(|(x, y)| (x - mean_x) * (y - mean_y))
+            .sum::<f64>();
+        let variance = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum::<f64>();
+        if variance == 0.0 || covariance <= 0.0 {
+            return None;
+        }
+        let slope = covariance / variance;
+        Some(Minutes((std::f64::consts::LN_2 / slope).round() as usize))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use structure::time::Time;
+    use structure::time::TimeUnit::{Days, Hours, Minutes};
+
+    use crate::game::population::CompartmentCounts;
+    use crate::game::recorder::{EpidemicRecorder, EpidemicSnapshot};
+
+    #[test]
+    fn doubling_time_of_exponential_growth() {
+        let mut recorder = EpidemicRecorder::new();
+        assert!(recorder.doubling_time().is_none());
+
+        // cases double every 3 days for 30 days, then everyone recovers over the next 10 days
+        for hour in (0..40 * 24).step_by(6) {
+            let growth = usize::min(hour, 30 * 24) as f64 / (3.0 * 24.0);
+            let cases = (10.0 * 2f64.powf(growth)).round() as usize;
+            let recovered = cases * hour.saturating_sub(30 * 24) / (10 * 24);
+            recorder.series.push(EpidemicSnapshot {
+                time: Hours(hour),
+                compartments: CompartmentCounts {
+                    susceptible: 20_000 - cases,
+                    infectious: cases - recovered,
+                    recovered,
+                    ..CompartmentCounts::default()
+                },
+            });
+        }
+
+        let doubling = usize::from(recorder.doubling_time().unwrap().as_minutes());
+        let expected = usize::from(Days(3).as_minutes());
+        assert!(
+            (doubling as f64 - expected as f64).abs() < 0.02 * expected as f64,
+            "Estimated a doubling time of {} minutes, expected {}",
+            doubling,
+            expected
+        );
+        assert!(recorder.doubling_time().unwrap() > Minutes(0));
+    }
+
+    #[test]
+    fn no_peak_without_active_cases() {
+        let mut recorder = EpidemicRecorder::new();
+        // recording started after everyone infected had already recovered or died
+        for day in 1..5 {
+            recorder.series.push(EpidemicSnapshot {
+                time: Days(day),
+                compartments: CompartmentCounts {
+                    susceptible: 90,
+                    recovered: 8,
+                    dead: 2,
+                    ..CompartmentCounts::default()
+                },
+            });
+        }
+
+        assert!(recorder.time_to_peak().is_none());
+        assert!(recorder.epidemic_duration().is_some());
+    }
+}
@@ -0,0 +1,381 @@
+use std::sync::{Arc, Mutex};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use structure::time::TimeUnit;
+
+use crate::game::population::person_behavior::interaction::{
+    InteractionController, InteractionSettings,
+};
+use crate::game::population::person_behavior::Controller;
+use crate::game::population::{PersonBuilder, Population};
+use crate::game::recorder::EpidemicRecorder;
+use crate::game::{tick_to_game_time_conversion, Update};
+
+///
+/// Runs a population and its interactions against an internal clock, without ever sleeping
+/// for real time
+pub struct Simulation {
+    population: Arc<Mutex<Population>>,
+    controller: InteractionController,
+    recorder: EpidemicRecorder,
+    ticks: usize,
+    run_recorder: Option<SimulationRecorder>,
+}
+
+impl Simulation {
+    pub fn new(population: Population) -> Self {
+        let population = Arc::new(Mutex::new(population));
+        let controller = InteractionController::new(&population);
+        Self {
+            population,
+            controller,
+            recorder: EpidemicRecorder::new(),
+            ticks: 0,
+            run_recorder: None,
+        }
+    }
+
+    /// Creates a simulation where every roll is drawn from `seed`, which records everything
+    /// needed to replay the run with [SimulationRecorder::replay]
+    pub fn recorded(population: Population, seed: u64) -> Self {
+        let run_recorder = SimulationRecorder::new(&population, seed);
+        let mut simulation = Self::new(population);
+        simulation.seed(seed);
+        simulation.run_recorder = Some(run_recorder);
+        simulation
+    }
+
+    /// Seeds both the population's updates and the interactions from `seed`
+    fn seed(&mut self, seed: u64) {
+        let mut seeds = StdRng::seed_from_u64(seed);
+        self.controller.set_seed(seeds.gen());
+        self.population
+            .lock()
+            .expect("Should be able to get the population")
+            .set_seed(seeds.gen());
+    }
+
+    /// Everything needed to replay the run, if the simulation is being recorded
+    pub fn run_recorder(&self) -> Option<&SimulationRecorder> {
+        self.run_recorder.as_ref()
+    }
+
+    pub fn population(&self) -> &Arc<Mutex<Population>> {
+        &self.population
+    }
+
+    pub fn controller(&self) -> &InteractionController {
+        &self.controller
+    }
+
+    pub fn controller_mut(&mut self) -> &mut InteractionController {
+        &mut self.controller
+    }
+
+    /// The compartments of the population after every step
+    pub fn recorder(&self) -> &EpidemicRecorder {
+        &self.recorder
+    }
+
+    /// The total amount of ticks that have been simulated
+    pub fn ticks(&self) -> usize {
+        self.ticks
+    }
+
+    /// The amount of game time that has been simulated
+    pub fn elapsed(&self) -> TimeUnit {
+        tick_to_game_time_conversion(self.ticks)
+    }
+
+    /// Updates the population by `delta_ticks`, runs a round of interactions, and then records
+    /// the state of the population
+    pub fn step(&mut self, delta_ticks: usize) {
+        self.population
+            .lock()
+            .expect("Should be able to get the population")
+            .update(delta_ticks);
+        if let Some(run_recorder) = &mut self.run_recorder {
+            run_recorder.record_step(delta_ticks, &self.controller);
+        }
+        self.controller.run();
+        self.ticks += delta_ticks;
+
+        let elapsed = self.elapsed();
+        let population = self
+            .population
+            .lock()
+            .expect("Should be able to get the population");
+        self.recorder.record(elapsed, &population);
+    }
+}
+
+///
+/// Everything needed to reproduce a simulation run, which is the population it started from, the
+/// seed that every roll was drawn from, the interaction settings, and every step it took. The
+/// pathogens of the run are carried by the infected of the initial population.
+///
+/// A replay starts every interaction rule over from a fresh copy, so it only matches the run if
+/// its rules were fresh when it started
+pub struct SimulationRecorder {
+    seed: u64,
+    initial: Population,
+    steps: Vec<usize>, // the ticks of every step, in order
+    settings: Vec<(usize, InteractionSettings)>, // the interaction settings from each step on, whenever they changed
+}
+
+impl SimulationRecorder {
+    /// Starts recording a run of `population`, keeping a copy of it as it is now
+    pub fn new(population: &Population, seed: u64) -> Self {
+        Self {
+            seed,
+            initial: population.deep_copy(&PersonBuilder::new()),
+            steps: Vec::new(),
+            settings: Vec::new(),
+        }
+    }
+
+    /// Records a step of `ticks`, along with the settings of `controller` if they changed since
+    /// the last step
+    fn record_step(&mut self, ticks: usize, controller: &InteractionController) {
+        let settings = controller.settings();
+        if self.settings.last().map(|(_, last)| last) != Some(&settings) {
+            self.settings.push((self.steps.len(), settings));
+        }
+        self.steps.push(ticks);
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// The population as it was when the run started
+    pub fn initial_population(&self) -> &Population {
+        &self.initial
+    }
+
+    /// The ticks of every step that was taken, in order
+    pub fn steps(&self) -> &[usize] {
+        &self.steps
+    }
+
+    /// The interaction settings of the run, each with the index of the first step that used them
+    pub fn settings(&self) -> &[(usize, InteractionSettings)] {
+        &self.settings
+    }
+
+    /// Runs every recorded step again on a fresh copy of the initial population, with the same
+    /// seed and interaction settings, and gets the resulting simulation
+    pub fn replay(&self) -> Simulation {
+        let mut simulation = Simulation::new(self.initial.deep_copy(&PersonBuilder::new()));
+        simulation.seed(self.seed);
+        // every rule of the run is replaced by a fresh one, which is shared wherever the run
+        // shared the original
+        let mut fresh: Vec<InteractionSettings> = Vec::new();
+        for (index, (_, settings)) in self.settings.iter().enumerate() {
+            let rule = settings.interaction_rule();
+            let rule = match self.settings[..index]
+                .iter()
+                .position(|(_, earlier)| Arc::ptr_eq(earlier.interaction_rule(), rule))
+            {
+                Some(earlier) => fresh[earlier].interaction_rule().clone(),
+                None => rule.fresh(),
+            };
+            fresh.push(settings.with_interaction_rule(rule));
+        }
+        let mut settings = self
+            .settings
+            .iter()
+            .map(|(from, _)| from)
+            .zip(&fresh)
+            .peekable();
+        for (step, &ticks) in self.steps.iter().enumerate() {
+            if let Some((_, next)) = settings.next_if(|(from, _)| **from == step) {
+                simulation.controller.apply_settings(next);
+            }
+            simulation.step(ticks);
+        }
+        simulation
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+    use std::sync::Arc;
+
+    use structure::graph::Graph;
+    use structure::time::TimeUnit::{Days, Hours, Minutes};
+
+    use crate::game::pathogen::symptoms::base::cheat::{CustomCatchChance, CustomFatality};
+    use crate::game::pathogen::symptoms::Symp;
+    use crate::game::pathogen::types::{PathogenType, Virus};
+    use crate::game::pathogen::Pathogen;
+    use crate::game::population::person_behavior::interaction::{
+        ContactDistribution, ExposureAccumulationRule,
+    };
+    use crate::game::population::{MortalityModel, PersonBuilder, Population, UniformDistribution};
+    use crate::game::simulation::Simulation;
+
+    #[test]
+    fn elapsed_tracks_steps() {
+        let pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            100,
+            UniformDistribution::new(20, 60),
+        );
+        let mut simulation = Simulation::new(pop);
+        assert_eq!(simulation.elapsed(), Minutes(0));
+
+        for _ in 0..60 {
+            simulation.step(20);
+        }
+        assert_eq!(simulation.elapsed(), Hours(1));
+
+        // partial game minutes still add up
+        simulation.step(10);
+        simulation.step(10);
+        assert_eq!(simulation.elapsed(), Minutes(61));
+        assert_eq!(simulation.ticks(), 60 * 20 + 20);
+    }
+
+    #[test]
+    fn peak_matches_series() {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            200,
+            UniformDistribution::new(20, 60),
+        );
+        let mut pathogen = Virus.create_pathogen("Test", 0);
+        pathogen.acquire_symptom(&CustomCatchChance(99.0).get_symptom(), None);
+        let pathogen = Arc::new(pathogen);
+        for _ in 0..5 {
+            assert!(pop.infect_one(&pathogen));
+        }
+
+        let mut simulation = Simulation::new(pop);
+        for _ in 0..300 {
+            simulation.step(20 * 15);
+        }
+
+        let series = simulation.recorder().infected_series();
+        assert_eq!(series.len(), 300);
+        let max = series.iter().map(|(_, count)| *count).max().unwrap();
+        let (time, peak) = simulation.recorder().peak_infected();
+        assert_eq!(peak, max);
+        assert!(peak >= 5);
+        let first_at_peak = series.iter().find(|(_, count)| *count == max).unwrap();
+        assert_eq!(time, first_at_peak.0);
+        assert!(time <= simulation.elapsed());
+    }
+
+    #[test]
+    fn replay_matches_recorded_run() {
+        // within host growth, mutations and per tick damage all roll while updating the population
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            300,
+            UniformDistribution::new(20, 60),
+        );
+        pop.set_mortality_model(MortalityModel::PerTick);
+        let mut p = Pathogen::new(
+            "Test".to_string(),
+            200,
+            0.1,
+            3 * 24 * 60,
+            12 * 60,
+            Graph::new(),
+            HashSet::new(),
+        );
+        p.acquire_symptom(&CustomCatchChance(50.0).get_symptom(), None);
+        p.acquire_symptom(&CustomFatality(2.0).get_symptom(), None);
+        let pathogen = Arc::new(p);
+        assert!(pop.infect_person(0, &pathogen));
+
+        let mut simulation = Simulation::recorded(pop, 21);
+        let rule = Arc::new(ExposureAccumulationRule::new(2.0, Days(1)));
+        let controller = simulation.controller_mut();
+        controller.set_contact_distribution(ContactDistribution::Poisson);
+        controller.set_interaction_rule(rule.clone());
+        for step in 0..4 * 24 {
+            if step == 24 {
+                simulation.controller_mut().set_time_step(Hours(1));
+            }
+            if step == 26 {
+                // replaying partway through leaves the run itself as it was
+                let exposed = (0..300).filter(|id| rule.exposure(*id) > 0.0).count();
+                assert!(exposed > 0);
+                simulation.run_recorder().unwrap().replay();
+                assert_eq!(
+                    (0..300).filter(|id| rule.exposure(*id) > 0.0).count(),
+                    exposed
+                );
+            }
+            simulation.step(20 * 60);
+        }
+        let run = simulation.run_recorder().unwrap();
+        assert_eq!(run.seed(), 21);
+        assert_eq!(run.steps().len(), 4 * 24);
+        assert_eq!(run.settings().len(), 2);
+        assert_eq!(run.settings()[1].0, 24);
+        assert_eq!(run.initial_population().statistics().ever_infected, 1);
+
+        let replayed = run.replay();
+        let stats = simulation.population().lock().unwrap().statistics();
+        assert!(
+            stats.ever_infected > 1,
+            "The index case never infected anyone"
+        );
+        assert_eq!(replayed.population().lock().unwrap().statistics(), stats);
+        assert_eq!(
+            replayed.recorder().infected_series(),
+            simulation.recorder().infected_series()
+        );
+    }
+
+    #[test]
+    fn peak_comes_before_the_end() {
+        let mut pop = Population::new(
+            &PersonBuilder::new(),
+            0.0,
+            300,
+            UniformDistribution::new(20, 60),
+        );
+        pop.set_mortality_model(MortalityModel::CaseFatality(0.0));
+        let mut p = Pathogen::new(
+            "Test".to_string(),
+            0,
+            0.0,
+            2 * 24 * 60,
+            0,
+            Graph::new(),
+            HashSet::new(),
+        );
+        p.acquire_symptom(&CustomCatchChance(10.0).get_symptom(), None);
+        let pathogen = Arc::new(p);
+        for id in 0..5 {
+            assert!(pop.infect_person(id, &pathogen));
+        }
+
+        let mut simulation = Simulation::recorded(pop, 2193);
+        assert!(simulation.recorder().time_to_peak().is_none());
+        while simulation.recorder().epidemic_duration().is_none() {
+            assert!(simulation.elapsed() < Days(100), "The epidemic never ended");
+            simulation.step(20 * 60);
+        }
+
+        let time_to_peak = simulation.recorder().time_to_peak().unwrap();
+        let duration = simulation.recorder().epidemic_duration().unwrap();
+        assert!(time_to_peak > Minutes(0));
+        assert!(
+            time_to_peak < duration,
+            "Peaked after {:?}, but the epidemic only lasted {:?}",
+            time_to_peak,
+            duration
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use std::fmt::{Debug, Formatter, Result};
+use std::fmt::{Debug, Display, Formatter, Result};
 use std::hash::Hash;
 use std::ops::{Deref, Index, IndexMut};
 
@@ -61,6 +61,70 @@ impl<ID: PartialEq + Copy, T> Node<ID, T> {
     }
 }
 
+///
+/// A node that was already looked up in a graph, along with its outgoing edges, so that code
+/// touching the same node many times doesn't have to hash its id every time
+pub struct NodeHandle<'g, ID, W, T>
+where
+    ID: Eq + Hash + Copy,
+{
+    node: &'g Node<ID, T>,
+    adjacency: Option<&'g HashMap<ID, W>>,
+}
+
+impl<ID, W, T> Clone for NodeHandle<'_, ID, W, T>
+where
+    ID: Eq + Hash + Copy,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<ID, W, T> Copy for NodeHandle<'_, ID, W, T> where ID: Eq + Hash + Copy {}
+
+impl<'g, ID, W, T> NodeHandle<'g, ID, W, T>
+where
+    ID: Eq + Hash + Copy,
+{
+    pub fn id(&self) -> &'g ID {
+        &self.node.id
+    }
+
+    pub fn value(&self) -> &'g T {
+        &self.node.value
+    }
+
+    pub fn node(&self) -> &'g Node<ID, T> {
+        self.node
+    }
+
+    /// Gets the ids of the nodes this node has an edge to
+    pub fn adjacent(&self) -> Vec<&'g ID> {
+        match self.adjacency {
+            None => Vec::new(),
+            Some(map) => map.keys().collect(),
+        }
+    }
+
+    /// Gets the weight of the edge from this node to `v`
+    pub fn weight_to(&self, v: &ID) -> Option<&'g W> {
+        self.adjacency.and_then(|map| map.get(v))
+    }
+}
+
+impl<'g, ID, W, T> NodeHandle<'g, ID, W, T>
+where
+    ID: Eq + Hash + Copy + Ord,
+{
+    /// Gets the adjacent nodes in ascending order, like [Graph::get_adjacent_sorted]
+    pub fn adjacent_sorted(&self) -> Vec<&'g ID> {
+        let mut output = self.adjacent();
+        output.sort();
+        output
+    }
+}
+
 /// Represents a graph
 ///
 /// `ID` must be impl `Hash`, `Eq`, and `Copy`
@@ -80,6 +144,8 @@ pub enum GraphError<ID> {
     IdExists(ID),
     IdDoesNotExist(ID),
     EdgeAlreadyExists,
+    EdgeDoesNotExist(ID, ID),
+    InvalidWeight(ID, ID),
 }
 
 pub type GraphResult<ID> = std::result::Result<(), GraphError<ID>>;
@@ -98,6 +164,30 @@ where
         }
     }
 
+    /// Creates an empty graph with room for at least `nodes` nodes and `edges` edges, so large
+    /// graphs aren't reallocated while they're being built
+    pub fn with_capacity(nodes: usize, edges: usize) -> Self {
+        Graph {
+            adjacency: HashMap::with_capacity(nodes),
+            nodes: HashMap::with_capacity(nodes),
+            edges: Vec::with_capacity(edges),
+            num_nodes: 0,
+            num_edges: 0,
+        }
+    }
+
+    /// Makes room for at least `nodes` more nodes and `edges` more edges
+    pub fn reserve(&mut self, nodes: usize, edges: usize) {
+        self.adjacency.reserve(nodes);
+        self.nodes.reserve(nodes);
+        self.edges.reserve(edges);
+    }
+
+    /// The amount of edges the graph can hold without reallocating
+    pub fn edge_capacity(&self) -> usize {
+        self.edges.capacity()
+    }
+
     pub fn get(&self, id: &ID) -> Option<&T> {
         match self.get_node(id) {
             None => None,
@@ -120,6 +210,16 @@ where
         self.nodes.get_mut(id)
     }
 
+    /// Looks up a node once, getting a handle that can access its value and its outgoing edges
+    /// without looking it up again
+    pub fn resolve(&self, id: ID) -> Option<NodeHandle<'_, ID, W, T>> {
+        let node = self.nodes.get(&id)?;
+        Some(NodeHandle {
+            node,
+            adjacency: self.adjacency.get(&id),
+        })
+    }
+
     pub fn add_node(&mut self, id: ID, value: T) -> GraphResult<ID> {
         let n = Node::new(id.clone(), value);
         if self.nodes.contains_key(n.get_id()) {
@@ -147,6 +247,7 @@ where
         }
         self.edges.push((u, v));
         map.insert(v, weight);
+        self.num_edges += 1;
         Ok(())
     }
 
@@ -168,6 +269,14 @@ where
         }
     }
 
+    pub fn get_weight_mut(&mut self, u: ID, v: ID) -> Option<&mut W> {
+        if !self.contains_edge(u, v) {
+            None
+        } else {
+            self.adjacency.get_mut(&u).unwrap().get_mut(&v)
+        }
+    }
+
     pub fn get_adjacent(&self, node: ID) -> Vec<&ID> {
         match self.adjacency.get(&node) {
             None => Vec::new(),
@@ -175,15 +284,72 @@ where
         }
     }
 
+    /// The amount of nodes in the graph
+    pub fn len(&self) -> usize {
+        self.num_nodes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.num_nodes == 0
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.num_edges
+    }
+
+    pub fn node_ids(&self) -> impl Iterator<Item = &ID> {
+        self.nodes.keys()
+    }
+
     pub fn nodes(&self) -> impl Iterator<Item = &Node<ID, T>> {
         self.nodes.values()
     }
 
+    /// Iterates over every edge in the order they were added. The order is stable, so anything
+    /// built by walking the edges, like a pathogen's symptoms, comes out the same every run.
     pub fn edges(&self) -> impl Iterator<Item = &(ID, ID)> {
         self.edges.iter()
     }
 }
 
+impl<ID, W, T> Graph<ID, W, T>
+where
+    ID: Eq + Hash + Copy + Ord,
+{
+    /// Gets the adjacent nodes in ascending order, so iteration doesn't depend on hashing
+    pub fn get_adjacent_sorted(&self, node: ID) -> Vec<&ID> {
+        let mut output = self.get_adjacent(node);
+        output.sort();
+        output
+    }
+}
+
+impl<ID, W, T> Graph<ID, W, T>
+where
+    ID: Eq + Hash + Copy + Ord + Display,
+    W: Display,
+{
+    /// Writes the graph in the Graphviz DOT format, labeling each node with `label` and each edge
+    /// with its weight. Nodes and edges are written in ascending order of their ids.
+    pub fn to_dot<F: Fn(&T) -> String>(&self, label: F) -> String {
+        let mut output = String::from("digraph {\n");
+        let mut ids = self.node_ids().collect::<Vec<_>>();
+        ids.sort();
+        for id in &ids {
+            let node_label = label(self.get(id).unwrap()).replace('"', "\\\"");
+            output.push_str(&format!("    {} [label=\"{}\"];\n", id, node_label));
+        }
+        for id in &ids {
+            for to in self.get_adjacent_sorted(**id) {
+                let weight = self.get_weight(**id, *to).unwrap();
+                output.push_str(&format!("    {} -> {} [label=\"{}\"];\n", id, to, weight));
+            }
+        }
+        output.push('}');
+        output
+    }
+}
+
 impl<ID, W, T> Graph<ID, W, T>
 where
     ID: Eq + Hash + Copy,
@@ -316,6 +482,40 @@ mod test {
         assert_eq!(g[(1, 2)], 10.0)
     }
 
+    #[test]
+    fn modify_weight() {
+        let mut g: Graph = Graph::new();
+
+        g.add_nodes(0..10, ()).unwrap();
+        g.add_edge(1, 2, 10.0).unwrap();
+        *g.get_weight_mut(1, 2).unwrap() = 3.0;
+        assert_eq!(g.get_weight(1, 2).unwrap(), &3.0);
+        assert!(g.get_weight_mut(2, 1).is_none());
+    }
+
+    #[test]
+    fn counts() {
+        let mut g: Graph = Graph::new();
+        assert!(g.is_empty());
+        assert_eq!(g.len(), 0);
+        assert_eq!(g.edge_count(), 0);
+
+        g.add_nodes(0..5, ()).unwrap();
+        assert!(g.add_node(3, ()).is_err());
+        g.add_edge(0, 1, 1.0).unwrap();
+        g.add_edge(1, 0, 1.0).unwrap();
+        g.add_edge(3, 4, 1.0).unwrap();
+        assert!(g.add_edge(3, 4, 2.0).is_err());
+        assert!(g.add_edge(3, 9, 2.0).is_err());
+
+        assert!(!g.is_empty());
+        assert_eq!(g.len(), 5);
+        assert_eq!(g.edge_count(), 3);
+        let mut ids = g.node_ids().cloned().collect::<Vec<usize>>();
+        ids.sort();
+        assert_eq!(ids, vec![0, 1, 2, 3, 4]);
+    }
+
     #[test]
     fn change_value() {
         let mut g: Graph<i32, f64, i32> = Graph::new();
@@ -339,6 +539,42 @@ mod test {
         assert_eq!(v, vec![&1, &3, &7]);
     }
 
+    #[test]
+    fn get_adjacent_sorted() {
+        let mut g: Graph = Graph::new();
+
+        g.add_nodes(0..100, ()).unwrap();
+        for id in (1..100).rev() {
+            g.add_edge_default(0, id).unwrap();
+        }
+        let expected = (1..100).collect::<Vec<usize>>();
+
+        for _ in 0..10 {
+            let v = g.get_adjacent_sorted(0);
+            assert_eq!(v, expected.iter().collect::<Vec<&usize>>());
+        }
+        assert!(g.get_adjacent_sorted(50).is_empty());
+    }
+
+    #[test]
+    fn to_dot() {
+        let mut g: Graph<usize, f64, &str> = Graph::new();
+
+        g.add_node(1, "Cough").unwrap();
+        g.add_node(0, "Runny \"Nose\"").unwrap();
+        g.add_edge(0, 1, 0.5).unwrap();
+
+        let dot = g.to_dot(|name| name.to_string());
+        assert_eq!(
+            dot,
+            "digraph {\n    \
+             0 [label=\"Runny \\\"Nose\\\"\"];\n    \
+             1 [label=\"Cough\"];\n    \
+             0 -> 1 [label=\"0.5\"];\n\
+             }"
+        );
+    }
+
     #[derive(Clone, Copy)]
     struct Wrapper<T>(T);
 
@@ -352,4 +588,48 @@ mod test {
         g.add_edge(5, 7, 11.0).unwrap();
         assert_eq!(g_prime.get_weight(3, 5), g.get_weight(3, 5));
     }
+
+    #[test]
+    fn handles_match_ids() {
+        let mut g: Graph<usize, f64, &str> = Graph::new();
+        g.add_node(0, "Cough").unwrap();
+        g.add_node(1, "Fever").unwrap();
+        g.add_node(2, "Rash").unwrap();
+        g.add_edge(0, 2, 0.25).unwrap();
+        g.add_edge(0, 1, 0.5).unwrap();
+
+        for id in 0..3 {
+            let handle = g.resolve(id).unwrap();
+            assert_eq!(handle.id(), &id);
+            assert_eq!(handle.value(), g.get(&id).unwrap());
+            assert_eq!(handle.adjacent_sorted(), g.get_adjacent_sorted(id));
+            for v in 0..3 {
+                assert_eq!(handle.weight_to(&v), g.get_weight(id, v));
+            }
+        }
+        assert!(g.resolve(3).is_none());
+    }
+
+    #[test]
+    fn edges_keep_insertion_order() {
+        let mut g: Graph = Graph::with_capacity(50, 200);
+        let initial = g.edge_capacity();
+        assert!(initial >= 200);
+        g.add_nodes(0..50, ()).unwrap();
+
+        let mut added = Vec::new();
+        for i in 0..200 {
+            let (u, v) = ((i * 7) % 50, (i * 13 + i / 50) % 50);
+            if g.add_edge(u, v, i as f64).is_ok() {
+                added.push((u, v));
+            }
+        }
+        assert_eq!(g.edges().cloned().collect::<Vec<_>>(), added);
+        assert_eq!(g.edge_capacity(), initial);
+
+        let capacity = g.edge_capacity();
+        g.reserve(0, 100);
+        assert!(g.edge_capacity() >= added.len() + 100);
+        assert!(g.edge_capacity() >= capacity);
+    }
 }
@@ -1,6 +1,6 @@
 use std::cmp::Ordering;
 use std::fmt::{Display, Formatter,};
-use std::ops::{Add, Div, Mul, Rem, Sub};
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
 
 use num_traits::{AsPrimitive, PrimInt, Unsigned};
 
@@ -163,6 +163,73 @@ impl TimeUnit {
         let form = TimeFormat::new(self, format_string);
         format!("{}", form)
     }
+
+    ///
+    /// Moves this time by a signed offset. Times before zero saturate at zero minutes.
+    pub fn offset(&self, offset: TimeOffset) -> TimeUnit {
+        match offset {
+            TimeOffset::Later(t) => self.clone() + t,
+            TimeOffset::Earlier(t) => {
+                let minutes = usize::from(self.as_minutes());
+                Minutes(minutes.saturating_sub(usize::from(t.into_minutes())))
+            }
+        }
+    }
+}
+
+/// A signed amount of time relative to some reference time
+#[derive(Clone, Debug)]
+pub enum TimeOffset {
+    Later(TimeUnit),
+    Earlier(TimeUnit),
+}
+
+impl Neg for TimeOffset {
+    type Output = TimeOffset;
+
+    fn neg(self) -> Self::Output {
+        match self {
+            TimeOffset::Later(t) => TimeOffset::Earlier(t),
+            TimeOffset::Earlier(t) => TimeOffset::Later(t),
+        }
+    }
+}
+
+///
+/// Accumulates values that each held for some amount of time, such as the amount of infectious
+/// people between updates, to get their time weighted mean
+#[derive(Clone, Debug, Default)]
+pub struct TimeWeightedAccumulator {
+    weighted_sum: f64, // sum of every value times its duration in minutes
+    total_minutes: usize,
+}
+
+impl TimeWeightedAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a value that held for `duration`
+    pub fn add(&mut self, value: f64, duration: TimeUnit) {
+        let minutes = usize::from(duration.into_minutes());
+        self.weighted_sum += value * minutes as f64;
+        self.total_minutes += minutes;
+    }
+
+    /// The total amount of time that has been accumulated
+    pub fn total_duration(&self) -> TimeUnit {
+        Minutes(self.total_minutes)
+    }
+
+    /// The mean of every value weighted by how long it held, or `None` if no time has been
+    /// accumulated
+    pub fn mean(&self) -> Option<f64> {
+        if self.total_minutes == 0 {
+            None
+        } else {
+            Some(self.weighted_sum / self.total_minutes as f64)
+        }
+    }
 }
 
 pub trait Time: Into<usize> + PartialOrd<usize> + Clone {
@@ -614,6 +681,34 @@ mod test {
         assert!(lhs < rhs);
     }
 
+    #[test]
+    fn offset_time() {
+        let reference = Days(5);
+        assert_eq!(reference.offset(TimeOffset::Later(Days(3))), Days(8));
+        assert_eq!(reference.offset(TimeOffset::Earlier(Days(3))), Days(2));
+        assert_eq!(reference.offset(TimeOffset::Earlier(Hours(1))), Days(4) + Hours(23));
+        assert_eq!(reference.offset(-TimeOffset::Later(Days(3))), Days(2));
+    }
+
+    #[test]
+    fn offset_saturates_at_zero() {
+        let reference = Hours(2);
+        assert_eq!(reference.offset(TimeOffset::Earlier(Days(3))), Minutes(0));
+        assert_eq!(reference.offset(TimeOffset::Earlier(Hours(2))), Minutes(0));
+    }
+
+    #[test]
+    fn time_weighted_mean() {
+        let mut accumulator = TimeWeightedAccumulator::new();
+        assert_eq!(accumulator.mean(), None);
+
+        accumulator.add(10.0, Hours(1));
+        accumulator.add(40.0, Hours(2));
+        accumulator.add(100.0, Minutes(0));
+        assert_eq!(accumulator.total_duration(), Hours(3));
+        assert_eq!(accumulator.mean(), Some(30.0));
+    }
+
     #[test]
     fn time_remain() {
         let a = Months(12);